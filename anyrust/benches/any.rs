@@ -0,0 +1,76 @@
+use anyrust::*;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn arithmetic(c: &mut Criterion) {
+    c.bench_function("add i64", |b| {
+        b.iter(|| {
+            let mut sum = any(0_i64);
+            for i in 0..1000_i64 {
+                sum += any(black_box(i));
+            }
+            sum
+        })
+    });
+
+    c.bench_function("mul f64", |b| {
+        b.iter(|| {
+            let mut product = any(1.0);
+            for _ in 0..1000 {
+                product *= any(black_box(1.0001));
+            }
+            product
+        })
+    });
+
+    c.bench_function("add mixed", |b| {
+        b.iter(|| {
+            let mut sum = any(0.0);
+            for i in 0..1000_i32 {
+                sum = sum + any(black_box(i)) - any(black_box(1_u8));
+            }
+            sum
+        })
+    });
+}
+
+fn indexing(c: &mut Criterion) {
+    let array = Any::from((0..1000_i64).collect::<Vec<_>>());
+    c.bench_function("index array", |b| {
+        b.iter(|| {
+            let mut sum = any(0_i64);
+            for i in 0..1000_usize {
+                sum += array[black_box(i)].clone();
+            }
+            sum
+        })
+    });
+
+    let mut map = map! {};
+    for i in 0..100_i64 {
+        map.set(i.to_string(), i);
+    }
+    c.bench_function("index map", |b| {
+        b.iter(|| {
+            let mut sum = any(0_i64);
+            for i in 0..100_i64 {
+                sum += map[black_box(i.to_string())].clone();
+            }
+            sum
+        })
+    });
+}
+
+fn cloning(c: &mut Criterion) {
+    let array = Any::from((0..1000_i64).collect::<Vec<_>>());
+    c.bench_function("clone array", |b| b.iter(|| black_box(&array).clone()));
+
+    let nested = array![
+        map! { "name" => "John", "age" => 20, "tags" => array!["a", "b", "c"] },
+        map! { "name" => "Jane", "age" => 30, "tags" => array!["d", "e"] },
+        array![1, 2.5, "three", true, null.clone()]
+    ];
+    c.bench_function("clone nested", |b| b.iter(|| black_box(&nested).clone()));
+}
+
+criterion_group!(benches, arithmetic, indexing, cloning);
+criterion_main!(benches);
@@ -0,0 +1,841 @@
+//! JavaScript Array methods (Array.prototype)
+
+use std::cmp::Ordering;
+
+use crate::{string, value::Value, Any, Array, Function};
+
+/// callback for the element-wise Array methods (map, filter, find, some, every, ...)
+///
+/// Implemented for Rust closures that take the element (`|value: Any|`) or the element and its index
+/// (`|value: Any, index: usize|`), and for Function values, which are called with `params![value, index]`.
+/// The argument types of a closure must be written out, because they cannot be inferred through this trait.
+/**
+```
+use anyrust::*;
+
+let numbers = Array::from(vec![any(1), any(2), any(3)]);
+
+let doubled = numbers.map(|value: Any| value * any(2));
+assert_eq!(any(doubled), array![2, 4, 6]);
+
+let is_odd = function!(value => { any(value.to_integer() % 2 == 1) });
+assert_eq!(any(numbers.filter(is_odd)), array![1, 3]);
+```
+*/
+pub trait Callback<Args> {
+    /// Calls the callback with an element and its index.
+    fn invoke(&self, value: Any, index: usize) -> Any;
+}
+
+impl<F, R> Callback<(Any,)> for F
+where
+    F: Fn(Any) -> R,
+    R: Into<Any>,
+{
+    fn invoke(&self, value: Any, _index: usize) -> Any {
+        self(value).into()
+    }
+}
+
+impl<F, R> Callback<(Any, usize)> for F
+where
+    F: Fn(Any, usize) -> R,
+    R: Into<Any>,
+{
+    fn invoke(&self, value: Any, index: usize) -> Any {
+        self(value, index).into()
+    }
+}
+
+impl Callback<Function> for Function {
+    fn invoke(&self, value: Any, index: usize) -> Any {
+        self.call(Any::from(Array(vec![value, Any::from(index)])))
+    }
+}
+
+impl Callback<Function> for &Function {
+    fn invoke(&self, value: Any, index: usize) -> Any {
+        Callback::invoke(*self, value, index)
+    }
+}
+
+impl Callback<Any> for Any {
+    fn invoke(&self, value: Any, index: usize) -> Any {
+        self.call(Any::from(Array(vec![value, Any::from(index)])))
+    }
+}
+
+impl Callback<Any> for &Any {
+    fn invoke(&self, value: Any, index: usize) -> Any {
+        Callback::invoke(*self, value, index)
+    }
+}
+
+/// callback for [`Array::reduce`]
+///
+/// Implemented for Rust closures that take the accumulator and the element (`|acc: Any, value: Any|`),
+/// optionally followed by the index, and for Function values, which are called with `params![acc, value, index]`.
+pub trait Reducer<Args> {
+    /// Calls the reducer with the accumulator, an element and its index.
+    fn invoke(&self, accumulator: Any, value: Any, index: usize) -> Any;
+}
+
+impl<F, R> Reducer<(Any, Any)> for F
+where
+    F: Fn(Any, Any) -> R,
+    R: Into<Any>,
+{
+    fn invoke(&self, accumulator: Any, value: Any, _index: usize) -> Any {
+        self(accumulator, value).into()
+    }
+}
+
+impl<F, R> Reducer<(Any, Any, usize)> for F
+where
+    F: Fn(Any, Any, usize) -> R,
+    R: Into<Any>,
+{
+    fn invoke(&self, accumulator: Any, value: Any, index: usize) -> Any {
+        self(accumulator, value, index).into()
+    }
+}
+
+impl Reducer<Function> for Function {
+    fn invoke(&self, accumulator: Any, value: Any, index: usize) -> Any {
+        self.call(Any::from(Array(vec![accumulator, value, Any::from(index)])))
+    }
+}
+
+impl Reducer<Function> for &Function {
+    fn invoke(&self, accumulator: Any, value: Any, index: usize) -> Any {
+        Reducer::invoke(*self, accumulator, value, index)
+    }
+}
+
+impl Reducer<Any> for Any {
+    fn invoke(&self, accumulator: Any, value: Any, index: usize) -> Any {
+        self.call(Any::from(Array(vec![accumulator, value, Any::from(index)])))
+    }
+}
+
+impl Reducer<Any> for &Any {
+    fn invoke(&self, accumulator: Any, value: Any, index: usize) -> Any {
+        Reducer::invoke(*self, accumulator, value, index)
+    }
+}
+
+/// comparator for [`Array::sort_by`]
+///
+/// Like a JavaScript compare function, a negative result sorts `a` before `b`, a positive result sorts it after,
+/// and zero (or NaN) keeps their order.
+/// Implemented for closures `|a: Any, b: Any|` that return a number, for closures `|a: &Any, b: &Any|` that return
+/// an [`Ordering`] (such as [`Any::cmp_total`]), and for Function values, which are called with `params![a, b]`.
+pub trait Comparator<Args> {
+    /// Compares two elements.
+    fn compare(&self, a: &Any, b: &Any) -> Ordering;
+}
+
+/// Converts the result of a JavaScript compare function to an Ordering.
+fn to_ordering(result: Any) -> Ordering {
+    let result = result.to_float();
+    if result < 0.0 {
+        Ordering::Less
+    } else if result > 0.0 {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    }
+}
+
+impl<F, R> Comparator<(Any, Any)> for F
+where
+    F: Fn(Any, Any) -> R,
+    R: Into<Any>,
+{
+    fn compare(&self, a: &Any, b: &Any) -> Ordering {
+        to_ordering(self(a.clone(), b.clone()).into())
+    }
+}
+
+impl<F> Comparator<(&Any, &Any)> for F
+where
+    F: Fn(&Any, &Any) -> Ordering,
+{
+    fn compare(&self, a: &Any, b: &Any) -> Ordering {
+        self(a, b)
+    }
+}
+
+impl Comparator<Function> for Function {
+    fn compare(&self, a: &Any, b: &Any) -> Ordering {
+        to_ordering(self.call(Any::from(Array(vec![a.clone(), b.clone()]))))
+    }
+}
+
+impl Comparator<Function> for &Function {
+    fn compare(&self, a: &Any, b: &Any) -> Ordering {
+        Comparator::compare(*self, a, b)
+    }
+}
+
+impl Comparator<Any> for Any {
+    fn compare(&self, a: &Any, b: &Any) -> Ordering {
+        to_ordering(self.call(Any::from(Array(vec![a.clone(), b.clone()]))))
+    }
+}
+
+impl Comparator<Any> for &Any {
+    fn compare(&self, a: &Any, b: &Any) -> Ordering {
+        Comparator::compare(*self, a, b)
+    }
+}
+
+/// Resolves a relative index like JavaScript does.
+///
+/// A negative index counts back from the end, and the result is clamped to `0..=length`.
+fn relative_index(index: isize, length: usize) -> usize {
+    if index < 0 {
+        length.saturating_sub(index.unsigned_abs())
+    } else {
+        (index as usize).min(length)
+    }
+}
+
+/// Resolves a `start..end` range like `Array.prototype.slice`. A missing end means the end of the array.
+fn relative_range(start: isize, end: Option<isize>, length: usize) -> (usize, usize) {
+    let start = relative_index(start, length);
+    let end = end.map_or(length, |end| relative_index(end, length));
+    (start, end.max(start))
+}
+
+fn flatten_into(result: &mut Vec<Any>, values: &[Any], depth: usize) {
+    for value in values {
+        match &value.value {
+            Value::Array(array) if depth > 0 => flatten_into(result, &array.0, depth - 1),
+            _ => result.push(value.clone()),
+        }
+    }
+}
+
+/// Implements JavaScript Array methods.
+impl Array {
+    /// Returns a new array with the results of calling the callback on every element.
+    pub fn map<A>(&self, callback: impl Callback<A>) -> Array {
+        let values = self.0.iter().enumerate();
+        Array(
+            values
+                .map(|(i, value)| callback.invoke(value.clone(), i))
+                .collect(),
+        )
+    }
+
+    /// Returns a new array with the elements for which the callback returns a truthy value.
+    pub fn filter<A>(&self, callback: impl Callback<A>) -> Array {
+        let values = self.0.iter().enumerate();
+        Array(
+            values
+                .filter(|(i, value)| callback.invoke((*value).clone(), *i).to_boolean())
+                .map(|(_, value)| value.clone())
+                .collect(),
+        )
+    }
+
+    /// Calls the reducer on every element, passing the result of the previous call, and returns the final result.
+    pub fn reduce<A>(&self, reducer: impl Reducer<A>, initial: impl Into<Any>) -> Any {
+        let values = self.0.iter().enumerate();
+        values.fold(initial.into(), |accumulator, (i, value)| {
+            reducer.invoke(accumulator, value.clone(), i)
+        })
+    }
+
+    /// Returns the first element for which the callback returns a truthy value.
+    pub fn find<A>(&self, callback: impl Callback<A>) -> Option<Any> {
+        self.find_index(callback).map(|i| self.0[i].clone())
+    }
+
+    /// Returns the index of the first element for which the callback returns a truthy value.
+    pub fn find_index<A>(&self, callback: impl Callback<A>) -> Option<usize> {
+        let mut values = self.0.iter().enumerate();
+        values.position(|(i, value)| callback.invoke(value.clone(), i).to_boolean())
+    }
+
+    /// Returns true if the callback returns a truthy value for any element.
+    pub fn some<A>(&self, callback: impl Callback<A>) -> bool {
+        self.find_index(callback).is_some()
+    }
+
+    /// Returns true if the callback returns a truthy value for every element.
+    pub fn every<A>(&self, callback: impl Callback<A>) -> bool {
+        let mut values = self.0.iter().enumerate();
+        values.all(|(i, value)| callback.invoke(value.clone(), i).to_boolean())
+    }
+
+    /// Returns true if the array contains the value. Unlike `index_of`, NaN is found.
+    pub fn includes(&self, value: impl Into<Any>) -> bool {
+        self.0.contains(&value.into())
+    }
+
+    /// Returns the index of the first element equal to the value with `===` (see [`Any::strict_eq`]).
+    pub fn index_of(&self, value: impl Into<Any>) -> Option<usize> {
+        let value = value.into();
+        self.0.iter().position(|element| element.strict_eq(&value))
+    }
+
+    /// Returns a copy of the `start..end` part of the array.
+    ///
+    /// Negative indices count back from the end, and a `None` end means the end of the array.
+    pub fn slice(&self, start: isize, end: impl Into<Option<isize>>) -> Array {
+        let (start, end) = relative_range(start, end.into(), self.0.len());
+        Array(self.0[start..end].to_vec())
+    }
+
+    /// Removes `delete_count` elements from `start`, inserts the items in their place, and returns the removed elements.
+    ///
+    /// A negative start counts back from the end.
+    pub fn splice(
+        &mut self,
+        start: isize,
+        delete_count: usize,
+        items: impl IntoIterator<Item = Any>,
+    ) -> Array {
+        let start = relative_index(start, self.0.len());
+        let end = start.saturating_add(delete_count).min(self.0.len());
+        Array(self.0.splice(start..end, items).collect())
+    }
+
+    /// Returns a new array with the value appended. If the value is an array, its elements are appended.
+    pub fn concat(&self, value: impl Into<Any>) -> Array {
+        let value = value.into();
+        let mut result = self.0.clone();
+        match value.value {
+            Value::Array(array) => result.extend(array.0),
+            _ => result.push(value),
+        }
+        Array(result)
+    }
+
+    /// Joins the elements into a string with the separator.
+    ///
+    /// Null and undefined become an empty string, and nested arrays are joined with `,` like in JavaScript.
+    pub fn join(&self, separator: &str) -> String {
+        let values = self.0.iter().map(|value| match &value.value {
+            Value::Null | Value::Undefined => String::new(),
+            Value::Array(array) => array.join(","),
+            _ => value.to_str(),
+        });
+        values.collect::<Vec<_>>().join(separator)
+    }
+
+    /// Returns a new array with the nested arrays flattened up to the depth.
+    pub fn flat(&self, depth: usize) -> Array {
+        let mut result = Vec::with_capacity(self.0.len());
+        flatten_into(&mut result, &self.0, depth);
+        Array(result)
+    }
+
+    /// Maps every element with the callback, then flattens the result by one level.
+    pub fn flat_map<A>(&self, callback: impl Callback<A>) -> Array {
+        self.map(callback).flat(1)
+    }
+
+    /// Sets every element in the `start..end` part of the array to the value.
+    ///
+    /// Negative indices count back from the end, and a `None` end means the end of the array.
+    pub fn fill(
+        &mut self,
+        value: impl Into<Any>,
+        start: isize,
+        end: impl Into<Option<isize>>,
+    ) -> &mut Self {
+        let value = value.into();
+        let (start, end) = relative_range(start, end.into(), self.0.len());
+        self.0[start..end].fill(value);
+        self
+    }
+
+    /// Returns the element at the index. A negative index counts back from the end.
+    pub fn at(&self, index: isize) -> Option<Any> {
+        let index = if index < 0 {
+            self.0.len().checked_sub(index.unsigned_abs())?
+        } else {
+            index as usize
+        };
+        self.0.get(index).cloned()
+    }
+
+    /// Sorts the array in place by the string form of the elements, like `Array.prototype.sort()` without a compare function.
+    pub fn sort(&mut self) -> &mut Self {
+        self.0.sort_by_cached_key(|value| value.to_str());
+        self
+    }
+
+    /// Sorts the array in place with the comparator. The sort is stable.
+    ///
+    /// Like JavaScript, a comparator that is not consistent gives an unspecified order, but never panics.
+    pub fn sort_by<A>(&mut self, comparator: impl Comparator<A>) -> &mut Self {
+        merge_sort(&mut self.0, &|a, b| comparator.compare(a, b));
+        self
+    }
+}
+
+/// Stable merge sort.
+///
+/// `slice::sort_by` may panic if the comparator is not a total order, which a user compare function does not guarantee.
+fn merge_sort(values: &mut Vec<Any>, compare: &impl Fn(&Any, &Any) -> Ordering) {
+    if values.len() <= 1 {
+        return;
+    }
+
+    let mut right = values.split_off(values.len() / 2);
+    merge_sort(values, compare);
+    merge_sort(&mut right, compare);
+
+    let mut left = std::mem::take(values).into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+        // an element of the right half only moves ahead if it is strictly smaller, which keeps the sort stable
+        let next = match compare(a, b) {
+            Ordering::Greater => right.next(),
+            _ => left.next(),
+        };
+        values.extend(next);
+    }
+    values.extend(left);
+    values.extend(right);
+}
+
+/// Implements JavaScript Array methods for Any.
+///
+/// The methods that do not modify the array treat any other value like `to_array()` does,
+/// except `includes`, `index_of` and `slice`, which work on the string form of the value.
+/// The methods that modify the array leave other values unchanged.
+impl Any {
+    fn with_array<R>(&self, f: impl FnOnce(&Array) -> R) -> R {
+        match &self.value {
+            Value::Array(array) => f(array),
+            _ => f(&self.to_array()),
+        }
+    }
+
+    /// Returns a new array with the results of calling the callback on every element.
+    pub fn map<A>(&self, callback: impl Callback<A>) -> Any {
+        self.with_array(|array| array.map(callback)).into()
+    }
+
+    /// Returns a new array with the elements for which the callback returns a truthy value.
+    pub fn filter<A>(&self, callback: impl Callback<A>) -> Any {
+        self.with_array(|array| array.filter(callback)).into()
+    }
+
+    /// Calls the reducer on every element, passing the result of the previous call, and returns the final result.
+    pub fn reduce<A>(&self, reducer: impl Reducer<A>, initial: impl Into<Any>) -> Any {
+        self.with_array(|array| array.reduce(reducer, initial))
+    }
+
+    /// Returns the first element for which the callback returns a truthy value, or undefined.
+    pub fn find<A>(&self, callback: impl Callback<A>) -> Any {
+        self.with_array(|array| array.find(callback))
+            .unwrap_or_else(|| Any::from(crate::_undefined))
+    }
+
+    /// Returns the index of the first element for which the callback returns a truthy value, or -1.
+    pub fn find_index<A>(&self, callback: impl Callback<A>) -> Any {
+        let index = self.with_array(|array| array.find_index(callback));
+        index.map_or(Any::from(-1_i64), |i| Any::from(i as i64))
+    }
+
+    /// Returns true if the callback returns a truthy value for any element.
+    pub fn some<A>(&self, callback: impl Callback<A>) -> bool {
+        self.with_array(|array| array.some(callback))
+    }
+
+    /// Returns true if the callback returns a truthy value for every element.
+    pub fn every<A>(&self, callback: impl Callback<A>) -> bool {
+        self.with_array(|array| array.every(callback))
+    }
+
+    /// Returns true if the array contains the value. Unlike `index_of`, NaN is found.
+    ///
+    /// For any other value, returns true if its string form contains the string form of the value.
+    pub fn includes(&self, value: impl Into<Any>) -> bool {
+        match &self.value {
+            Value::Array(array) => array.includes(value),
+            _ => self.with_str(|string| string::includes(string, &value.into().to_str())),
+        }
+    }
+
+    /// Returns the index of the first element equal to the value, or -1.
+    ///
+    /// For any other value, returns the char index of the string form of the value in its string form.
+    pub fn index_of(&self, value: impl Into<Any>) -> Any {
+        let index = match &self.value {
+            Value::Array(array) => array.index_of(value),
+            _ => self.with_str(|string| string::index_of(string, &value.into().to_str())),
+        };
+        index.map_or(Any::from(-1_i64), |i| Any::from(i as i64))
+    }
+
+    /// Returns a copy of the `start..end` part of the array.
+    ///
+    /// Negative indices count back from the end, and a `None` end means the end of the array.
+    /// For any other value, returns the `start..end` chars of its string form.
+    pub fn slice(&self, start: isize, end: impl Into<Option<isize>>) -> Any {
+        match &self.value {
+            Value::Array(array) => array.slice(start, end).into(),
+            _ => self.with_str(|string| string::slice(string, start, end.into()).into()),
+        }
+    }
+
+    /// Removes `delete_count` elements from `start`, inserts the items in their place, and returns the removed elements.
+    pub fn splice(
+        &mut self,
+        start: isize,
+        delete_count: usize,
+        items: impl IntoIterator<Item = Any>,
+    ) -> Any {
+        match &mut self.value {
+            Value::Array(array) => array.splice(start, delete_count, items).into(),
+            _ => Array::new().into(),
+        }
+    }
+
+    /// Returns a new array with the value appended. If the value is an array, its elements are appended.
+    pub fn concat(&self, value: impl Into<Any>) -> Any {
+        self.with_array(|array| array.concat(value)).into()
+    }
+
+    /// Joins the elements into a string with the separator.
+    pub fn join(&self, separator: &str) -> Any {
+        self.with_array(|array| array.join(separator)).into()
+    }
+
+    /// Returns a new array with the nested arrays flattened up to the depth.
+    pub fn flat(&self, depth: usize) -> Any {
+        self.with_array(|array| array.flat(depth)).into()
+    }
+
+    /// Maps every element with the callback, then flattens the result by one level.
+    pub fn flat_map<A>(&self, callback: impl Callback<A>) -> Any {
+        self.with_array(|array| array.flat_map(callback)).into()
+    }
+
+    /// Sets every element in the `start..end` part of the array to the value.
+    pub fn fill(
+        &mut self,
+        value: impl Into<Any>,
+        start: isize,
+        end: impl Into<Option<isize>>,
+    ) -> Any {
+        match &mut self.value {
+            Value::Array(array) => array.fill(value, start, end).clone().into(),
+            _ => self.clone(),
+        }
+    }
+
+    /// Returns the element at the index, or undefined. A negative index counts back from the end.
+    pub fn at(&self, index: isize) -> Any {
+        self.with_array(|array| array.at(index))
+            .unwrap_or_else(|| Any::from(crate::_undefined))
+    }
+
+    /// Sorts the array in place by the string form of the elements, and returns it.
+    pub fn sort(&mut self) -> &mut Self {
+        if let Value::Array(array) = &mut self.value {
+            array.sort();
+        }
+        self
+    }
+
+    /// Sorts the array in place with the comparator, and returns it. The sort is stable.
+    pub fn sort_by<A>(&mut self, comparator: impl Comparator<A>) -> &mut Self {
+        if let Value::Array(array) = &mut self.value {
+            array.sort_by(comparator);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod test_array_methods {
+    use super::*;
+    use crate as anyrust;
+    use crate::{_null, array, function};
+
+    fn numbers() -> Array {
+        Array::from(vec![Any::from(1), Any::from(2), Any::from(3), Any::from(4)])
+    }
+
+    #[test]
+    fn test_map() {
+        let result = numbers().map(|value: Any| value * Any::from(10));
+        assert_eq!(Any::from(result), array![10, 20, 30, 40]);
+
+        let result = numbers().map(|_: Any, index: usize| index);
+        assert_eq!(
+            Any::from(result),
+            array![0_usize, 1_usize, 2_usize, 3_usize]
+        );
+
+        let add_index = function!(value, index => { value + index });
+        let result = numbers().map(&add_index);
+        assert_eq!(Any::from(result), array![1_i64, 3_i64, 5_i64, 7_i64]);
+    }
+
+    #[test]
+    fn test_filter() {
+        let result = numbers().filter(|value: Any| value.to_integer() % 2 == 0);
+        assert_eq!(Any::from(result), array![2, 4]);
+
+        let result = numbers().filter(|_: Any, index: usize| index < 2);
+        assert_eq!(Any::from(result), array![1, 2]);
+    }
+
+    #[test]
+    fn test_reduce() {
+        let sum = numbers().reduce(|acc: Any, value: Any| acc + value, 0);
+        assert_eq!(sum, Any::from(10));
+
+        let joined = numbers().reduce(
+            |acc: Any, value: Any, index: usize| acc + value * Any::from(index),
+            "",
+        );
+        assert_eq!(joined, Any::from("02612"));
+
+        let product = function!(acc, value => { acc * value });
+        assert_eq!(numbers().reduce(product, 1), Any::from(24));
+
+        assert_eq!(
+            Array::new().reduce(|acc: Any, _: Any| acc, "empty"),
+            Any::from("empty")
+        );
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(
+            numbers().find(|value: Any| value.to_integer() > 2),
+            Some(Any::from(3))
+        );
+        assert_eq!(numbers().find(|value: Any| value.to_integer() > 10), None);
+
+        assert_eq!(
+            numbers().find_index(|value: Any| value.to_integer() > 2),
+            Some(2)
+        );
+        assert_eq!(
+            numbers().find_index(|value: Any| value.to_integer() > 10),
+            None
+        );
+    }
+
+    #[test]
+    fn test_some_every() {
+        assert!(numbers().some(|value: Any| value.to_integer() == 3));
+        assert!(!numbers().some(|value: Any| value.to_integer() == 5));
+        assert!(numbers().every(|value: Any| value.to_integer() > 0));
+        assert!(!numbers().every(|value: Any| value.to_integer() > 1));
+
+        assert!(!Array::new().some(|_: Any| true));
+        assert!(Array::new().every(|_: Any| false));
+    }
+
+    #[test]
+    fn test_includes_index_of() {
+        let a = Array::from(vec![Any::from(1), Any::from("two"), Any::from(f64::NAN)]);
+        assert!(a.includes("two"));
+        assert!(!a.includes("three"));
+        assert!(a.includes(f64::NAN));
+
+        assert_eq!(a.index_of("two"), Some(1));
+        assert_eq!(a.index_of("three"), None);
+        assert_eq!(a.index_of(1.0), Some(0));
+        assert_eq!(a.index_of(f64::NAN), None);
+    }
+
+    #[test]
+    fn test_slice() {
+        assert_eq!(Any::from(numbers().slice(1, 3)), array![2, 3]);
+        assert_eq!(Any::from(numbers().slice(1, None)), array![2, 3, 4]);
+        assert_eq!(Any::from(numbers().slice(-2, None)), array![3, 4]);
+        assert_eq!(Any::from(numbers().slice(0, -1)), array![1, 2, 3]);
+        assert_eq!(Any::from(numbers().slice(3, 1)), array![]);
+        assert_eq!(Any::from(numbers().slice(-10, 10)), array![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_splice() {
+        let mut a = numbers();
+        let removed = a.splice(1, 2, vec![Any::from("a"), Any::from("b"), Any::from("c")]);
+        assert_eq!(Any::from(removed), array![2, 3]);
+        assert_eq!(Any::from(a.clone()), array![1, "a", "b", "c", 4]);
+
+        let removed = a.splice(-1, 10, vec![]);
+        assert_eq!(Any::from(removed), array![4]);
+        assert_eq!(Any::from(a), array![1, "a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_concat_join() {
+        let a = numbers().concat(array![5, 6]).concat(7);
+        assert_eq!(Any::from(a.clone()), array![1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(a.join("-"), "1-2-3-4-5-6-7");
+
+        let b = Array::from(vec![
+            Any::from(1),
+            Any::from(_null),
+            array![2, 3],
+            Any::from("x"),
+        ]);
+        assert_eq!(b.join(", "), "1, , 2,3, x");
+        assert_eq!(Array::new().join(","), "");
+    }
+
+    #[test]
+    fn test_flat() {
+        let a = Array::from(vec![Any::from(1), array![2, array![3, array![4]]]]);
+        assert_eq!(Any::from(a.flat(0)), Any::from(a.clone()));
+        assert_eq!(Any::from(a.flat(1)), array![1, 2, array![3, array![4]]]);
+        assert_eq!(Any::from(a.flat(usize::MAX)), array![1, 2, 3, 4]);
+
+        let result = numbers().flat_map(|value: Any| array![value.clone(), value]);
+        assert_eq!(Any::from(result), array![1, 1, 2, 2, 3, 3, 4, 4]);
+    }
+
+    #[test]
+    fn test_fill() {
+        let mut a = numbers();
+        a.fill(0, 1, 3);
+        assert_eq!(Any::from(a.clone()), array![1, 0, 0, 4]);
+
+        a.fill("x", -1, None);
+        assert_eq!(Any::from(a), array![1, 0, 0, "x"]);
+    }
+
+    #[test]
+    fn test_at() {
+        assert_eq!(numbers().at(0), Some(Any::from(1)));
+        assert_eq!(numbers().at(-1), Some(Any::from(4)));
+        assert_eq!(numbers().at(4), None);
+        assert_eq!(numbers().at(-5), None);
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut a = Array::from(vec![
+            Any::from(10),
+            Any::from(9),
+            Any::from(1),
+            Any::from("b"),
+            Any::from("a"),
+        ]);
+        a.sort();
+        assert_eq!(Any::from(a.clone()), array![1, 10, 9, "a", "b"]);
+
+        let mut a = Array::from(vec![Any::from(10), Any::from(9), Any::from(1.5)]);
+        a.sort_by(|a: Any, b: Any| a - b);
+        assert_eq!(Any::from(a.clone()), array![1.5, 9, 10]);
+
+        let descending = function!(a, b => { b - a });
+        a.sort_by(descending);
+        assert_eq!(Any::from(a.clone()), array![10, 9, 1.5]);
+
+        a.sort_by(Any::cmp_total);
+        assert_eq!(Any::from(a), array![1.5, 9, 10]);
+    }
+
+    #[test]
+    fn test_sort_inconsistent_comparator() {
+        let values = (0..100)
+            .map(|i| Any::from((i * 37) % 100))
+            .collect::<Vec<_>>();
+
+        let comparators = [
+            function!(_a, _b => { Any::from(1) }),
+            function!(_a, _b => { Any::from(-1) }),
+            function!(a, b => { Any::from((a.to_integer() + b.to_integer()) % 3 - 1) }),
+        ];
+        for comparator in comparators {
+            let mut a = Array::from(values.clone());
+            a.sort_by(&comparator);
+
+            let mut sorted = a.0.clone();
+            sorted.sort_by(Any::cmp_total);
+            assert_eq!(sorted, (0..100).map(Any::from).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_sort_is_stable() {
+        let mut a = Array::from(vec![
+            array!["b", 1],
+            array!["a", 2],
+            array!["b", 3],
+            array!["a", 4],
+        ]);
+        a.sort_by(|a: &Any, b: &Any| a[0].to_str().cmp(&b[0].to_str()));
+        assert_eq!(
+            Any::from(a),
+            array![
+                array!["a", 2],
+                array!["a", 4],
+                array!["b", 1],
+                array!["b", 3]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_any_methods() {
+        let mut a = array![3, 1, 2];
+
+        assert_eq!(a.map(|value: Any| value + Any::from(1)), array![4, 2, 3]);
+        assert_eq!(a.filter(|value: Any| value.to_integer() > 1), array![3, 2]);
+        assert_eq!(
+            a.reduce(|acc: Any, value: Any| acc + value, 0),
+            Any::from(6)
+        );
+        assert_eq!(a.find(|value: Any| value.to_integer() < 3), Any::from(1));
+        assert!(a.find(|value: Any| value.to_integer() > 3).is_undefined());
+        assert_eq!(
+            a.find_index(|value: Any| value.to_integer() == 2),
+            Any::from(2_i64)
+        );
+        assert_eq!(
+            a.find_index(|value: Any| value.to_integer() == 5),
+            Any::from(-1_i64)
+        );
+        assert!(a.some(|value: Any| value.to_integer() == 1));
+        assert!(a.every(|value: Any| value.to_integer() > 0));
+        assert!(a.includes(2));
+        assert_eq!(a.index_of(2), Any::from(2_i64));
+        assert_eq!(a.index_of(5), Any::from(-1_i64));
+        assert_eq!(a.slice(1, None), array![1, 2]);
+        assert_eq!(a.concat(4), array![3, 1, 2, 4]);
+        assert_eq!(a.join("+"), Any::from("3+1+2"));
+        assert_eq!(array![1, array![2]].flat(1), array![1, 2]);
+        assert_eq!(a.flat_map(|value: Any| array![value]), array![3, 1, 2]);
+        assert_eq!(a.at(-1), Any::from(2));
+        assert!(a.at(3).is_undefined());
+
+        let double = function!(value => { value * Any::from(2) });
+        assert_eq!(a.map(&double), array![6, 2, 4]);
+
+        assert_eq!(*a.sort(), array![1, 2, 3]);
+        assert_eq!(*a.sort_by(|a: Any, b: Any| b - a), array![3, 2, 1]);
+        assert_eq!(a.splice(0, 1, vec![]), array![3]);
+        assert_eq!(a.fill(0, 0, None), array![0, 0]);
+        assert_eq!(a, array![0, 0]);
+    }
+
+    #[test]
+    fn test_any_methods_on_non_array() {
+        let mut value = Any::from(5);
+        assert_eq!(value.map(|value: Any| value * Any::from(2)), array![10]);
+        assert!(value.includes(5));
+        assert_eq!(Any::from(_null).map(|value: Any| value), array![]);
+
+        assert_eq!(value.splice(0, 1, vec![]), array![]);
+        assert_eq!(*value.sort(), Any::from(5));
+        assert_eq!(value, Any::from(5));
+    }
+}
@@ -0,0 +1,358 @@
+//! arbitrary-precision integers, like JavaScript `BigInt`
+
+use std::cmp::Ordering;
+
+use num_bigint::{BigInt, BigUint};
+use num_traits::{FromPrimitive, One, Signed, ToPrimitive, Zero};
+
+use crate::{
+    value::Value, Any, Array, CastError, IntOp, Map, ToArray, ToBoolean, ToFloat, ToFunction,
+    ToInteger, ToMap, ToPair, ToStr,
+};
+
+/// Values outside of i64 saturate.
+impl ToInteger for BigInt {
+    fn to_integer(&self) -> i64 {
+        self.to_i64().unwrap_or(if self.is_negative() {
+            i64::MIN
+        } else {
+            i64::MAX
+        })
+    }
+}
+
+impl ToFloat for BigInt {
+    fn to_float(&self) -> f64 {
+        self.to_f64().unwrap_or(f64::NAN)
+    }
+}
+
+/// Converts to the decimal digits without the `n` suffix, like `String(1n)` in JavaScript.
+impl ToStr for BigInt {
+    fn to_str(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl ToArray for BigInt {
+    fn to_array(&self) -> Array {
+        Array::new()
+    }
+}
+
+impl ToMap for BigInt {
+    fn to_map(&self) -> Map {
+        Map::new()
+    }
+}
+
+impl ToBoolean for BigInt {
+    fn to_boolean(&self) -> bool {
+        !self.is_zero()
+    }
+}
+
+impl ToPair for BigInt {}
+
+impl ToFunction for BigInt {}
+
+impl From<BigInt> for Any {
+    fn from(value: BigInt) -> Self {
+        Any::new(value)
+    }
+}
+
+/// i128 does not fit in a JavaScript number, so it is stored as a BigInt.
+impl From<i128> for Any {
+    fn from(value: i128) -> Self {
+        Any::new(BigInt::from(value))
+    }
+}
+
+/// u128 does not fit in a JavaScript number, so it is stored as a BigInt.
+impl From<u128> for Any {
+    fn from(value: u128) -> Self {
+        Any::new(BigInt::from(value))
+    }
+}
+
+/// Parses decimal digits with an optional sign and an optional `n` suffix. (surrounding whitespace is ignored)
+pub(crate) fn parse_bigint(text: &str) -> Option<BigInt> {
+    let text = text.trim();
+    let text = text.strip_suffix('n').unwrap_or(text);
+    let digits = text.strip_prefix(['+', '-']).unwrap_or(text);
+
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    text.parse().ok()
+}
+
+/// Implements BigInt support.
+impl Any {
+    /// Parses a BigInt from decimal digits. The `n` suffix that Display writes is accepted, so `to_string()` round-trips.
+    /// ```
+    /// use anyrust::*;
+    ///
+    /// let a = Any::parse_bigint("123456789012345678901234567890").unwrap();
+    /// assert_eq!(a.to_string(), "123456789012345678901234567890n");
+    /// assert_eq!(Any::parse_bigint(&a.to_string()), Ok(a));
+    /// assert!(Any::parse_bigint("1.5").is_err());
+    /// ```
+    pub fn parse_bigint(text: &str) -> Result<Any, CastError> {
+        parse_bigint(text)
+            .map(Any::from)
+            .ok_or(CastError::new("String", "BigInt"))
+    }
+
+    /// Returns true if the type is a BigInt.
+    pub fn is_bigint(&self) -> bool {
+        matches!(self.value, Value::BigInt(_))
+    }
+
+    /// Converts the type to a BigInt, like `BigInt()` in JavaScript.
+    ///
+    /// Succeeds for integers, integral floats, booleans and strings of decimal digits.
+    pub fn try_to_bigint(&self) -> Result<BigInt, CastError> {
+        let bigint = match &self.value {
+            Value::BigInt(bigint) => Some((**bigint).clone()),
+            Value::Int(int) => Some(BigInt::from(int.to_i128())),
+            Value::Bool(boolean) => Some(BigInt::from(*boolean as u8)),
+            Value::Float(float) if float.to_f64().fract() == 0.0 => {
+                BigInt::from_f64(float.to_f64())
+            }
+            Value::Str(string) => parse_bigint(string),
+            _ => None,
+        };
+
+        bigint.ok_or_else(|| self.cast_error("BigInt"))
+    }
+}
+
+const MIXED_TYPES: &str = "Cannot mix BigInt and other types, use explicit conversions";
+
+/// largest BigInt in bits, like the limit in V8 (a BigInt of this size takes 128MB)
+pub const MAX_BIGINT_BITS: u64 = 1 << 30;
+
+/// Applies an arithmetic operator to two BigInts. Returns None if either is not a BigInt, or for a zero divisor.
+pub(crate) fn checked_bigint_arithmetic(a: &Any, b: &Any, op: IntOp) -> Option<Any> {
+    let (Value::BigInt(a), Value::BigInt(b)) = (&a.value, &b.value) else {
+        return None;
+    };
+    let (a, b) = (&**a, &**b);
+
+    let result = match op {
+        IntOp::Add => a + b,
+        IntOp::Sub => a - b,
+        IntOp::Mul => a * b,
+        IntOp::Div | IntOp::Rem if b.is_zero() => return None,
+        IntOp::Div => a / b,
+        IntOp::Rem => a % b,
+    };
+
+    Some(Any::from(result))
+}
+
+/// Applies an arithmetic operator with a BigInt operand.
+///
+/// Like JavaScript, this panics if the other operand is not a BigInt (TypeError) or on division by zero (RangeError).
+pub(crate) fn bigint_arithmetic(a: &Any, b: &Any, op: IntOp) -> Any {
+    if !(a.is_bigint() && b.is_bigint()) {
+        panic!("{MIXED_TYPES}");
+    }
+
+    checked_bigint_arithmetic(a, b, op).unwrap_or_else(|| panic!("Division by zero"))
+}
+
+/// `**` for BigInts. Panics like `bigint_arithmetic`, for a negative exponent,
+/// and if the result could have more than [`MAX_BIGINT_BITS`] bits.
+pub(crate) fn bigint_pow(base: &Any, exponent: &Any) -> Any {
+    let (Value::BigInt(base), Value::BigInt(exponent)) = (&base.value, &exponent.value) else {
+        panic!("{MIXED_TYPES}");
+    };
+
+    if exponent.is_negative() {
+        panic!("Exponent must be non-negative");
+    }
+
+    // 0, 1 and -1 keep their size whatever the exponent, and only its parity matters
+    if base.magnitude() <= &BigUint::one() {
+        let exponent = match (exponent.is_zero(), exponent.bit(0)) {
+            (true, _) => 0,
+            (false, true) => 1,
+            (false, false) => 2,
+        };
+        return Any::from(base.pow(exponent));
+    }
+
+    // checked before pow, so that a huge result is never allocated
+    match exponent.to_u64() {
+        Some(exponent) if base.bits().saturating_mul(exponent) <= MAX_BIGINT_BITS => {
+            Any::from(base.pow(exponent as u32))
+        }
+        _ => panic!("Maximum BigInt size exceeded"),
+    }
+}
+
+/// Compares a BigInt with a float exactly. Returns None if the float is NaN.
+pub(crate) fn compare_bigint_float(a: &BigInt, b: f64) -> Option<Ordering> {
+    if b.is_nan() {
+        None
+    } else if b.is_infinite() {
+        Some(if b > 0.0 {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        })
+    } else {
+        // the integral part converts without loss
+        match a.cmp(&BigInt::from_f64(b.trunc())?) {
+            Ordering::Equal => 0.0_f64.partial_cmp(&b.fract()),
+            ordering => Some(ordering),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_bigint {
+    use super::*;
+    use crate::{_null, any};
+
+    fn big(text: &str) -> Any {
+        Any::parse_bigint(text).unwrap()
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let a = big("-98765432109876543210");
+        assert_eq!(a.to_string(), "-98765432109876543210n");
+        assert_eq!(a.to_str(), "-98765432109876543210");
+        assert_eq!(big(&a.to_string()), a);
+        assert_eq!(big(" 42 "), Any::from(BigInt::from(42)));
+
+        assert!(Any::parse_bigint("").is_err());
+        assert!(Any::parse_bigint("n").is_err());
+        assert!(Any::parse_bigint("1e3").is_err());
+        assert!(Any::parse_bigint("12nn").is_err());
+    }
+
+    #[test]
+    fn test_conversion() {
+        assert_eq!(Any::from(i128::MIN).to_string(), format!("{}n", i128::MIN));
+        assert_eq!(Any::from(u128::MAX).to_string(), format!("{}n", u128::MAX));
+        assert!(Any::from(1_i128).is_bigint());
+
+        assert_eq!(any(5).try_to_bigint(), Ok(BigInt::from(5)));
+        assert_eq!(any(u64::MAX).try_to_bigint(), Ok(BigInt::from(u64::MAX)));
+        assert_eq!(
+            any(1e20).try_to_bigint(),
+            Ok(BigInt::from(100000000000000000000_u128))
+        );
+        assert_eq!(any(true).try_to_bigint(), Ok(BigInt::from(1)));
+        assert_eq!(any("-12").try_to_bigint(), Ok(BigInt::from(-12)));
+        assert_eq!(
+            any(1.5).try_to_bigint(),
+            Err(CastError::new("f64", "BigInt"))
+        );
+        assert_eq!(
+            any(f64::NAN).try_to_bigint(),
+            Err(CastError::new("f64", "BigInt"))
+        );
+        assert_eq!(
+            Any::from(_null).try_to_bigint(),
+            Err(CastError::new("Null", "BigInt"))
+        );
+
+        let a = big("100000000000000000000");
+        assert_eq!(a.to_integer(), i64::MAX);
+        assert_eq!(a.to_float(), 1e20);
+        assert!(a.to_boolean());
+        assert!(!big("0").to_boolean());
+        assert_eq!(a.try_to_integer(), Err(CastError::new("BigInt", "integer")));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = big("100000000000000000000");
+        let b = big("3");
+
+        assert_eq!(a.clone() + b.clone(), big("100000000000000000003"));
+        assert_eq!(a.clone() - b.clone(), big("99999999999999999997"));
+        assert_eq!(a.clone() * b.clone(), big("300000000000000000000"));
+        assert_eq!(a.clone() / b.clone(), big("33333333333333333333"));
+        assert_eq!(a.clone() % b.clone(), big("1"));
+        assert_eq!(-big("7") % b.clone(), big("-1"));
+        assert_eq!(-a.clone(), big("-100000000000000000000"));
+        assert_eq!(b.pow(big("50")), big("717897987691852588770249"));
+        assert_eq!(a.clone() + any("x"), any("100000000000000000000x"));
+        assert!((a.clone() + Any::from(_null)).is_null());
+
+        assert_eq!(a.checked_add(b.clone()), Some(big("100000000000000000003")));
+        assert_eq!(a.checked_div(big("0")), None);
+        assert_eq!(a.checked_add(1), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot mix BigInt and other types")]
+    fn test_mixed_arithmetic() {
+        let _ = big("1") + any(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Division by zero")]
+    fn test_division_by_zero() {
+        let _ = big("1") / big("0");
+    }
+
+    #[test]
+    fn test_pow() {
+        assert_eq!(big("2").pow(big("64")), big("18446744073709551616"));
+        assert_eq!(big("-2").pow(big("3")), big("-8"));
+        assert_eq!(big("5").pow(big("0")), big("1"));
+        assert_eq!(big("-1").pow(big("100000000000")), big("1"));
+        assert_eq!(big("-1").pow(big("100000000001")), big("-1"));
+        assert_eq!(big("0").pow(big("100000000000")), big("0"));
+        assert_eq!(big("0").pow(big("0")), big("1"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Maximum BigInt size exceeded")]
+    fn test_pow_size_limit() {
+        // 2^31 bits, twice the limit, but much smaller than u32::MAX
+        let _ = big("4").pow(big("1073741824"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Exponent must be non-negative")]
+    fn test_pow_negative_exponent() {
+        let _ = big("2").pow(big("-1"));
+    }
+
+    #[test]
+    fn test_comparison() {
+        let a = big("100000000000000000000");
+        assert_eq!(a, big("100000000000000000000"));
+        assert_ne!(big("1"), any(1));
+        assert!(a > any(i64::MAX));
+        assert!(a.lt(&any(1e21)));
+        assert!(big("1").lt(&any(1.5)));
+        assert!(big("2").gt(&any(1.5)));
+        assert!(big("1").js_ge(&any(1.0)));
+        assert_eq!(big("1").partial_cmp(&any(1.0)), None);
+        assert!(a.lt(&any(f64::INFINITY)));
+        assert_eq!(big("1").partial_cmp(&any(f64::NAN)), None);
+
+        assert_ne!(big("1").cmp_total(&any(1)), Ordering::Equal);
+        assert_eq!(big("1").cmp_total(&any(1.0)), Ordering::Greater);
+        assert_eq!(any(1).cmp_total(&big("1")), Ordering::Less);
+        assert_eq!(big("1").cmp_total(&any(1.5)), Ordering::Less);
+
+        let mut values = vec![big("10"), any(5), any(20.5), big("-1")];
+        values.sort_by(|a, b| a.cmp_total(b));
+        assert_eq!(values, vec![big("-1"), any(5), big("10"), any(20.5)]);
+
+        let set: std::collections::HashSet<Any> = [big("1"), big("1"), big("2")].into();
+        assert_eq!(set.len(), 2);
+    }
+}
@@ -0,0 +1,424 @@
+use crate::{
+    _null, _undefined, value::Value, Any, Array, BigInt, CastError, Decimal, Function, Map, Null,
+    Pair, Set, Undefined,
+};
+
+/// Converts a Rust value into Any.
+///
+/// Unlike `From`, this is also implemented for `Vec<T>` and `Option<T>` of any convertible `T`,
+/// so it is what `#[derive(IntoAny)]` uses for field values.
+pub trait IntoAny {
+    fn into_any(self) -> Any;
+}
+
+/// Converts an Any back into a Rust value. This is the inverse of [`IntoAny`].
+///
+/// Integers and floats are read with `try_to_integer` / `try_to_float`, so numeric strings are accepted.
+/// Integers that do not fit in the target type are an error.
+pub trait FromAny: Sized {
+    fn from_any(value: &Any) -> Result<Self, CastError>;
+}
+
+macro_rules! impl_convert_for_integer {
+    ($($t:ty),*) => {
+        $(
+            impl IntoAny for $t {
+                fn into_any(self) -> Any {
+                    Any::from(self)
+                }
+            }
+
+            impl FromAny for $t {
+                fn from_any(value: &Any) -> Result<Self, CastError> {
+                    let integer = match &value.value {
+                        Value::Int(int) => int.to_i128(),
+                        _ => value.try_to_integer()? as i128,
+                    };
+
+                    <$t>::try_from(integer).map_err(|_| value.cast_error(stringify!($t)))
+                }
+            }
+        )*
+    };
+}
+
+impl_convert_for_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+macro_rules! impl_convert_for_bigint {
+    ($($t:ty),*) => {
+        $(
+            impl IntoAny for $t {
+                fn into_any(self) -> Any {
+                    Any::from(self)
+                }
+            }
+
+            impl FromAny for $t {
+                fn from_any(value: &Any) -> Result<Self, CastError> {
+                    <$t>::try_from(value.try_to_bigint()?)
+                        .map_err(|_| value.cast_error(stringify!($t)))
+                }
+            }
+        )*
+    };
+}
+
+impl_convert_for_bigint!(i128, u128);
+
+impl IntoAny for BigInt {
+    fn into_any(self) -> Any {
+        Any::from(self)
+    }
+}
+
+impl FromAny for BigInt {
+    fn from_any(value: &Any) -> Result<Self, CastError> {
+        value.try_to_bigint()
+    }
+}
+
+impl IntoAny for Decimal {
+    fn into_any(self) -> Any {
+        Any::from(self)
+    }
+}
+
+impl FromAny for Decimal {
+    fn from_any(value: &Any) -> Result<Self, CastError> {
+        value.try_to_decimal()
+    }
+}
+
+impl IntoAny for Set {
+    fn into_any(self) -> Any {
+        Any::from(self)
+    }
+}
+
+impl FromAny for Set {
+    fn from_any(value: &Any) -> Result<Self, CastError> {
+        value.try_to_set()
+    }
+}
+
+impl IntoAny for f32 {
+    fn into_any(self) -> Any {
+        Any::from(self)
+    }
+}
+
+impl FromAny for f32 {
+    fn from_any(value: &Any) -> Result<Self, CastError> {
+        value.try_to_float().map(|float| float as f32)
+    }
+}
+
+impl IntoAny for f64 {
+    fn into_any(self) -> Any {
+        Any::from(self)
+    }
+}
+
+impl FromAny for f64 {
+    fn from_any(value: &Any) -> Result<Self, CastError> {
+        value.try_to_float()
+    }
+}
+
+impl IntoAny for bool {
+    fn into_any(self) -> Any {
+        Any::from(self)
+    }
+}
+
+impl FromAny for bool {
+    fn from_any(value: &Any) -> Result<Self, CastError> {
+        value.try_to_boolean()
+    }
+}
+
+impl IntoAny for char {
+    fn into_any(self) -> Any {
+        Any::from(self)
+    }
+}
+
+/// Only a string of exactly one char can be converted.
+impl FromAny for char {
+    fn from_any(value: &Any) -> Result<Self, CastError> {
+        if let Value::Str(string) = &value.value {
+            let mut chars = string.chars();
+
+            if let (Some(c), None) = (chars.next(), chars.next()) {
+                return Ok(c);
+            }
+        }
+
+        Err(value.cast_error("char"))
+    }
+}
+
+impl IntoAny for String {
+    fn into_any(self) -> Any {
+        Any::from(self)
+    }
+}
+
+impl IntoAny for &str {
+    fn into_any(self) -> Any {
+        Any::from(self)
+    }
+}
+
+/// Only strings can be converted. Use `to_str()` to stringify other values.
+impl FromAny for String {
+    fn from_any(value: &Any) -> Result<Self, CastError> {
+        match &value.value {
+            Value::Str(string) => Ok(string.clone()),
+            _ => Err(value.cast_error("String")),
+        }
+    }
+}
+
+impl IntoAny for Any {
+    fn into_any(self) -> Any {
+        self
+    }
+}
+
+impl FromAny for Any {
+    fn from_any(value: &Any) -> Result<Self, CastError> {
+        Ok(value.clone())
+    }
+}
+
+impl IntoAny for Array {
+    fn into_any(self) -> Any {
+        Any::from(self)
+    }
+}
+
+impl FromAny for Array {
+    fn from_any(value: &Any) -> Result<Self, CastError> {
+        value.try_to_array()
+    }
+}
+
+impl IntoAny for Map {
+    fn into_any(self) -> Any {
+        Any::from(self)
+    }
+}
+
+impl FromAny for Map {
+    fn from_any(value: &Any) -> Result<Self, CastError> {
+        value.try_to_map()
+    }
+}
+
+impl IntoAny for Pair {
+    fn into_any(self) -> Any {
+        Any::from(self)
+    }
+}
+
+impl FromAny for Pair {
+    fn from_any(value: &Any) -> Result<Self, CastError> {
+        value.try_to_pair()
+    }
+}
+
+impl IntoAny for Function {
+    fn into_any(self) -> Any {
+        Any::from(self)
+    }
+}
+
+impl FromAny for Function {
+    fn from_any(value: &Any) -> Result<Self, CastError> {
+        value.try_to_function()
+    }
+}
+
+impl IntoAny for Null {
+    fn into_any(self) -> Any {
+        Any::from(self)
+    }
+}
+
+impl FromAny for Null {
+    fn from_any(value: &Any) -> Result<Self, CastError> {
+        if value.is_null() {
+            Ok(_null)
+        } else {
+            Err(value.cast_error("Null"))
+        }
+    }
+}
+
+impl IntoAny for Undefined {
+    fn into_any(self) -> Any {
+        Any::from(self)
+    }
+}
+
+impl FromAny for Undefined {
+    fn from_any(value: &Any) -> Result<Self, CastError> {
+        if value.is_undefined() {
+            Ok(_undefined)
+        } else {
+            Err(value.cast_error("Undefined"))
+        }
+    }
+}
+
+impl IntoAny for () {
+    fn into_any(self) -> Any {
+        Any::from(_null)
+    }
+}
+
+impl FromAny for () {
+    fn from_any(value: &Any) -> Result<Self, CastError> {
+        Null::from_any(value).map(|_| ())
+    }
+}
+
+/// None becomes null.
+impl<T: IntoAny> IntoAny for Option<T> {
+    fn into_any(self) -> Any {
+        match self {
+            Some(value) => value.into_any(),
+            None => Any::from(_null),
+        }
+    }
+}
+
+/// null and undefined become None.
+impl<T: FromAny> FromAny for Option<T> {
+    fn from_any(value: &Any) -> Result<Self, CastError> {
+        if value.is_nullish() {
+            Ok(None)
+        } else {
+            T::from_any(value).map(Some)
+        }
+    }
+}
+
+impl<T: IntoAny> IntoAny for Vec<T> {
+    fn into_any(self) -> Any {
+        Any::from(Array(self.into_iter().map(IntoAny::into_any).collect()))
+    }
+}
+
+/// Only arrays can be converted. Every element must convert to `T`, and the index of a failing one is added to the error path.
+impl<T: FromAny> FromAny for Vec<T> {
+    fn from_any(value: &Any) -> Result<Self, CastError> {
+        match &value.value {
+            Value::Array(array) => array
+                .0
+                .iter()
+                .enumerate()
+                .map(|(index, value)| T::from_any(value).map_err(|error| error.with_path(index)))
+                .collect(),
+            _ => Err(value.cast_error("Array")),
+        }
+    }
+}
+
+impl<T: IntoAny> IntoAny for Box<T> {
+    fn into_any(self) -> Any {
+        (*self).into_any()
+    }
+}
+
+impl<T: FromAny> FromAny for Box<T> {
+    fn from_any(value: &Any) -> Result<Self, CastError> {
+        T::from_any(value).map(Box::new)
+    }
+}
+
+/// None becomes null.
+impl<T: Into<Any>> From<Option<T>> for Any {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => value.into(),
+            None => Any::from(_null),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_convert {
+    use super::*;
+
+    #[test]
+    fn test_into_any() {
+        assert_eq!(5_u8.into_any(), Any::from(5_u8));
+        assert_eq!("foo".into_any(), Any::from("foo"));
+        assert_eq!(Some(1).into_any(), Any::from(1));
+        assert!(None::<i32>.into_any().is_null());
+        assert_eq!(
+            vec![Some(1), None].into_any(),
+            Any::from(Array::from(vec![Any::from(1), Any::from(_null)]))
+        );
+        assert_eq!(vec![vec![1], vec![]].into_any().to_string(), "[[1], []]");
+        assert_eq!(Any::from(Some("foo")), Any::from("foo"));
+        assert!(Any::from(None::<i32>).is_null());
+    }
+
+    #[test]
+    fn test_from_any() {
+        assert_eq!(i32::from_any(&Any::from(5_i64)), Ok(5));
+        assert_eq!(i32::from_any(&Any::from("42")), Ok(42));
+        assert_eq!(u64::from_any(&Any::from(u64::MAX)), Ok(u64::MAX));
+        assert_eq!(
+            u8::from_any(&Any::from(300)),
+            Err(CastError::new("i32", "u8"))
+        );
+        assert_eq!(
+            u8::from_any(&Any::from(-1)),
+            Err(CastError::new("i32", "u8"))
+        );
+        assert_eq!(u128::from_any(&Any::from(u128::MAX)), Ok(u128::MAX));
+        assert_eq!(i128::from_any(&Any::from(-5)), Ok(-5));
+        assert_eq!(
+            u128::from_any(&Any::from(-1_i128)),
+            Err(CastError::new("BigInt", "u128"))
+        );
+        assert_eq!(
+            BigInt::from_any(&Any::from("12345678901234567890")),
+            Ok(BigInt::from(12345678901234567890_u64))
+        );
+        assert_eq!(f64::from_any(&Any::from(1.5)), Ok(1.5));
+        assert_eq!(
+            Decimal::from_any(&Any::from("0.10")),
+            Ok(Decimal::new(10, 2))
+        );
+        assert_eq!(bool::from_any(&Any::from(true)), Ok(true));
+        assert_eq!(char::from_any(&Any::from("a")), Ok('a'));
+        assert_eq!(
+            char::from_any(&Any::from("ab")),
+            Err(CastError::new("String", "char"))
+        );
+        assert_eq!(String::from_any(&Any::from("foo")), Ok("foo".to_string()));
+        assert_eq!(
+            String::from_any(&Any::from(1)),
+            Err(CastError::new("i32", "String"))
+        );
+        assert_eq!(Option::<i32>::from_any(&Any::from(_null)), Ok(None));
+        assert_eq!(Option::<i32>::from_any(&Any::from(1)), Ok(Some(1)));
+        assert_eq!(
+            Vec::<i32>::from_any(&Any::from(vec![1, 2, 3])),
+            Ok(vec![1, 2, 3])
+        );
+        assert_eq!(
+            Vec::<i32>::from_any(&Any::from(Array::from(vec![Any::from(1), Any::from("x")]))),
+            Err(CastError::new("String", "integer").with_path(1))
+        );
+        assert_eq!(
+            Vec::<i32>::from_any(&Any::from(Map::new())),
+            Err(CastError::new("Map", "Array"))
+        );
+    }
+}
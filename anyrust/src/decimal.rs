@@ -0,0 +1,461 @@
+//! exact base-10 numbers, for values like money that f64 cannot hold exactly
+
+use std::{cmp::Ordering, str::FromStr};
+
+use num_bigint::BigInt;
+use num_traits::{FromPrimitive, ToPrimitive};
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use crate::{
+    js_pow, numeric_operands, value::Value, Any, Array, CastError, IntOp, Map, Operands, ToArray,
+    ToBoolean, ToFloat, ToFunction, ToInteger, ToMap, ToPair, ToStr,
+};
+
+/// Truncates toward zero. Values outside of i64 saturate.
+impl ToInteger for Decimal {
+    fn to_integer(&self) -> i64 {
+        self.trunc().to_i64().unwrap_or(if self.is_sign_negative() {
+            i64::MIN
+        } else {
+            i64::MAX
+        })
+    }
+}
+
+impl ToFloat for Decimal {
+    fn to_float(&self) -> f64 {
+        self.to_f64().unwrap_or(f64::NAN)
+    }
+}
+
+/// Keeps the scale, so `1.50` stays `1.50`.
+impl ToStr for Decimal {
+    fn to_str(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl ToArray for Decimal {
+    fn to_array(&self) -> Array {
+        Array::new()
+    }
+}
+
+impl ToMap for Decimal {
+    fn to_map(&self) -> Map {
+        Map::new()
+    }
+}
+
+impl ToBoolean for Decimal {
+    fn to_boolean(&self) -> bool {
+        !self.is_zero()
+    }
+}
+
+impl ToPair for Decimal {}
+
+impl ToFunction for Decimal {}
+
+impl From<Decimal> for Any {
+    fn from(value: Decimal) -> Self {
+        Any::new(value)
+    }
+}
+
+/// Implements Decimal support.
+impl Any {
+    /// Parses a Decimal from a string like `"12.50"` or `"1e-3"`. The scale of the string is kept.
+    /// ```
+    /// use anyrust::*;
+    ///
+    /// let price = Any::parse_decimal("19.99").unwrap();
+    /// let total = price * any(3);
+    /// assert_eq!(total.to_string(), "59.97");
+    /// assert!(Any::parse_decimal("abc").is_err());
+    /// ```
+    pub fn parse_decimal(text: &str) -> Result<Any, CastError> {
+        parse_decimal(text)
+            .map(Any::from)
+            .ok_or(CastError::new("String", "Decimal"))
+    }
+
+    /// Returns true if the type is a Decimal.
+    pub fn is_decimal(&self) -> bool {
+        matches!(self.value, Value::Decimal(_))
+    }
+
+    /// Converts the type to a Decimal.
+    ///
+    /// Succeeds for numbers, booleans and numeric strings that fit in a Decimal (28 significant digits).
+    /// Floats are converted from their shortest representation, so `0.1` becomes exactly `0.1`.
+    pub fn try_to_decimal(&self) -> Result<Decimal, CastError> {
+        let decimal = match &self.value {
+            Value::Str(string) => parse_decimal(string),
+            _ => as_decimal(self),
+        };
+
+        decimal.ok_or_else(|| self.cast_error("Decimal"))
+    }
+
+    /// Rounds to exactly `scale` decimal places with the given rounding strategy, padding with zeros if needed.
+    ///
+    /// Other numbers and numeric strings are converted to a Decimal first. Anything else gives NaN.
+    /// ```
+    /// use anyrust::*;
+    ///
+    /// let amount = Any::parse_decimal("2.345").unwrap();
+    /// assert_eq!(amount.with_scale(2, RoundingStrategy::MidpointNearestEven).to_string(), "2.34");
+    /// assert_eq!(amount.with_scale(2, RoundingStrategy::MidpointAwayFromZero).to_string(), "2.35");
+    /// assert_eq!(any(5).with_scale(2, RoundingStrategy::ToZero).to_string(), "5.00");
+    /// ```
+    pub fn with_scale(&self, scale: u32, strategy: RoundingStrategy) -> Any {
+        match self.try_to_decimal() {
+            Ok(decimal) => {
+                let mut decimal = decimal.round_dp_with_strategy(scale, strategy);
+                decimal.rescale(scale);
+                Any::from(decimal)
+            }
+            Err(_) => Any::new(f64::NAN),
+        }
+    }
+}
+
+fn parse_decimal(text: &str) -> Option<Decimal> {
+    let text = text.trim();
+    Decimal::from_str(text)
+        .or_else(|_| Decimal::from_scientific(text))
+        .ok()
+}
+
+/// Converts a numeric operand to a Decimal. Returns None if it does not fit, or for NaN and infinities.
+pub(crate) fn as_decimal(value: &Any) -> Option<Decimal> {
+    match &value.value {
+        Value::Decimal(decimal) => Some(*decimal),
+        Value::Int(int) => Decimal::from_i128(int.to_i128()),
+        Value::Bool(boolean) => Some(Decimal::from(*boolean as u8)),
+        Value::Float(float) => Decimal::from_f64(float.to_f64()),
+        _ => None,
+    }
+}
+
+impl IntOp {
+    /// Returns None on overflow or for a zero divisor.
+    pub(crate) fn decimal(self, a: Decimal, b: Decimal) -> Option<Decimal> {
+        match self {
+            IntOp::Add => a.checked_add(b),
+            IntOp::Sub => a.checked_sub(b),
+            IntOp::Mul => a.checked_mul(b),
+            IntOp::Div => a.checked_div(b),
+            IntOp::Rem => a.checked_rem(b),
+        }
+    }
+}
+
+/// Applies an arithmetic operator to two Decimals.
+///
+/// Like integer arithmetic, a result that does not fit or a zero divisor falls back to f64,
+/// so an overflow gives an approximate float and division by zero gives Infinity or NaN instead of panicking.
+/// Use the `checked_*` methods to stay exact and get None in these cases.
+pub(crate) fn decimal_arithmetic(a: Decimal, b: Decimal, op: IntOp) -> Any {
+    match op.decimal(a, b) {
+        Some(decimal) => Any::new(decimal),
+        None => Any::new(op.float(a.to_float(), b.to_float())),
+    }
+}
+
+/// Returns both operands as Decimals if the arithmetic operators would compute in Decimal.
+pub(crate) fn decimal_operands(a: &Any, b: &Any) -> Option<(Decimal, Decimal)> {
+    if !(a.is_decimal() || b.is_decimal()) {
+        return None;
+    }
+
+    match numeric_operands(a, b)? {
+        Operands::Decimal(a, b) => Some((a, b)),
+        _ => None,
+    }
+}
+
+/// `**` for a Decimal base and an integer exponent. Falls back to f64 like `decimal_arithmetic`.
+pub(crate) fn decimal_pow(base: Decimal, exponent: i64) -> Any {
+    match checked_decimal_pow(base, exponent) {
+        Some(decimal) => Any::new(decimal),
+        None => Any::new(js_pow(base.to_float(), exponent as f64)),
+    }
+}
+
+/// `**` for a Decimal base and an integer exponent. Returns None on overflow.
+pub(crate) fn checked_decimal_pow(base: Decimal, exponent: i64) -> Option<Decimal> {
+    let mut result = Decimal::ONE;
+    let mut square = base;
+    let mut remaining = exponent.unsigned_abs();
+
+    while remaining > 0 {
+        if remaining & 1 == 1 {
+            result = result.checked_mul(square)?;
+        }
+        remaining >>= 1;
+        if remaining > 0 {
+            square = square.checked_mul(square)?;
+        }
+    }
+
+    if exponent < 0 {
+        Decimal::ONE.checked_div(result)
+    } else {
+        Some(result)
+    }
+}
+
+/// Compares a Decimal with a float exactly. Returns None if the float is NaN.
+pub(crate) fn compare_decimal_float(a: Decimal, b: f64) -> Option<Ordering> {
+    if b.is_nan() {
+        return None;
+    }
+    if b.is_infinite() {
+        return Some(if b > 0.0 {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        });
+    }
+
+    // a = mantissa / 10^scale and b = sign * mantissa * 2^exponent, so both sides are scaled to integers
+    let (mantissa, exponent, sign) = num_traits::Float::integer_decode(b);
+    let mut decimal = BigInt::from(a.mantissa());
+    let mut float = BigInt::from(mantissa) * sign * BigInt::from(10).pow(a.scale());
+    if exponent >= 0 {
+        float <<= exponent as usize;
+    } else {
+        decimal <<= exponent.unsigned_abs() as usize;
+    }
+
+    Some(decimal.cmp(&float))
+}
+
+/// Compares a Decimal with a BigInt exactly.
+pub(crate) fn compare_decimal_bigint(a: Decimal, b: &BigInt) -> Ordering {
+    // every Decimal fits in i128
+    let integral = BigInt::from(a.trunc().to_i128().unwrap_or_default());
+
+    match integral.cmp(b) {
+        Ordering::Equal => a.fract().cmp(&Decimal::ZERO),
+        ordering => ordering,
+    }
+}
+
+#[cfg(test)]
+mod test_decimal {
+    use super::*;
+    use crate::{_null, any};
+
+    fn dec(text: &str) -> Any {
+        Any::parse_decimal(text).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!(dec("1.50").to_string(), "1.50");
+        assert_eq!(dec(" -0.001 ").to_string(), "-0.001");
+        assert_eq!(dec("1e-3").to_string(), "0.001");
+        assert!(dec("1.5").is_decimal());
+        assert!(dec("1.5").is_number());
+
+        assert!(Any::parse_decimal("").is_err());
+        assert!(Any::parse_decimal("1.2.3").is_err());
+        assert!(Any::parse_decimal("NaN").is_err());
+    }
+
+    #[test]
+    fn test_conversion() {
+        assert_eq!(any(0.1).try_to_decimal(), Ok(Decimal::new(1, 1)));
+        assert_eq!(any(5_u8).try_to_decimal(), Ok(Decimal::from(5)));
+        assert_eq!(any("2.50").try_to_decimal(), Ok(Decimal::new(250, 2)));
+        assert_eq!(
+            any(f64::NAN).try_to_decimal(),
+            Err(CastError::new("f64", "Decimal"))
+        );
+        assert_eq!(
+            Any::from(_null).try_to_decimal(),
+            Err(CastError::new("Null", "Decimal"))
+        );
+
+        let a = dec("-2.75");
+        assert_eq!(a.to_integer(), -2);
+        assert_eq!(a.to_float(), -2.75);
+        assert_eq!(a.try_to_integer(), Ok(-2));
+        assert!(a.to_boolean());
+        assert!(!dec("0.00").to_boolean());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        struct TestCase {
+            name: String,
+            result: Any,
+            expected: Any,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                name: "decimal + decimal is exact".to_string(),
+                result: dec("0.1") + dec("0.2"),
+                expected: dec("0.3"),
+            },
+            TestCase {
+                name: "decimal - integer".to_string(),
+                result: dec("10.00") - any(3),
+                expected: dec("7.00"),
+            },
+            TestCase {
+                name: "integer * decimal".to_string(),
+                result: any(3_u8) * dec("19.99"),
+                expected: dec("59.97"),
+            },
+            TestCase {
+                name: "decimal / decimal".to_string(),
+                result: dec("1") / dec("4"),
+                expected: dec("0.25"),
+            },
+            TestCase {
+                name: "decimal % integer".to_string(),
+                result: dec("7.5") % any(2),
+                expected: dec("1.5"),
+            },
+            TestCase {
+                name: "decimal + float".to_string(),
+                result: dec("0.5") + any(0.25),
+                expected: any(0.75),
+            },
+            TestCase {
+                name: "decimal + bool".to_string(),
+                result: dec("0.5") + any(true),
+                expected: dec("1.5"),
+            },
+            TestCase {
+                name: "decimal + string".to_string(),
+                result: dec("1.50") + any("$"),
+                expected: any("1.50$"),
+            },
+            TestCase {
+                name: "neg".to_string(),
+                result: -dec("1.25"),
+                expected: dec("-1.25"),
+            },
+            TestCase {
+                name: "pow".to_string(),
+                result: dec("1.1").pow(2),
+                expected: dec("1.21"),
+            },
+            TestCase {
+                name: "negative pow".to_string(),
+                result: dec("2").pow(-2),
+                expected: dec("0.25"),
+            },
+        ];
+
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.result, test_case.expected,
+                "TC: {}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_division_by_zero() {
+        assert_eq!(dec("1") / dec("0"), any(f64::INFINITY));
+        assert_eq!(dec("-1") / any(0), any(f64::NEG_INFINITY));
+        assert!((dec("0") / dec("0")).is_nan());
+        assert!((dec("1") % dec("0")).is_nan());
+        assert_eq!(dec("0").pow(-1), any(f64::INFINITY));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let result = Any::from(Decimal::MAX) + dec("1");
+        assert!(result.is_float());
+        assert_eq!(result, any(Decimal::MAX.to_f64().unwrap() + 1.0));
+
+        let result = Any::from(Decimal::MAX) * 2;
+        assert_eq!(result, any(Decimal::MAX.to_f64().unwrap() * 2.0));
+    }
+
+    #[test]
+    fn test_pow_overflow() {
+        let result = dec("10").pow(100);
+        assert!(result.is_float());
+        assert_eq!(result, any(1e100));
+    }
+
+    #[test]
+    fn test_checked() {
+        assert_eq!(dec("1").checked_div(dec("0")), None);
+        assert_eq!(dec("0").checked_rem(any(0)), None);
+        assert_eq!(Any::from(Decimal::MAX).checked_add(dec("1")), None);
+        assert_eq!(Any::from(Decimal::MAX).checked_mul(2), None);
+        assert_eq!(dec("1.5").checked_mul(2), Some(dec("3")));
+        // a float operand computes in f64, so it never overflows
+        assert_eq!(dec("1").checked_div(any(0.0)), Some(any(f64::INFINITY)));
+    }
+
+    #[test]
+    fn test_with_scale() {
+        let a = dec("2.345");
+        assert_eq!(
+            a.with_scale(2, RoundingStrategy::MidpointNearestEven)
+                .to_string(),
+            "2.34"
+        );
+        assert_eq!(
+            a.with_scale(2, RoundingStrategy::MidpointAwayFromZero)
+                .to_string(),
+            "2.35"
+        );
+        assert_eq!(a.with_scale(0, RoundingStrategy::ToZero).to_string(), "2");
+        assert_eq!(
+            (dec("10") / dec("3"))
+                .with_scale(4, RoundingStrategy::MidpointNearestEven)
+                .to_string(),
+            "3.3333"
+        );
+        assert_eq!(
+            any("1.5")
+                .with_scale(3, RoundingStrategy::ToZero)
+                .to_string(),
+            "1.500"
+        );
+        assert!(any("abc").with_scale(2, RoundingStrategy::ToZero).is_nan());
+    }
+
+    #[test]
+    fn test_eq_and_hash() {
+        use std::collections::HashSet;
+
+        assert_eq!(dec("1.50"), dec("1.5"));
+        assert_ne!(dec("1.5"), dec("1.51"));
+
+        let set: HashSet<Any> = [dec("1.50"), dec("1.5"), dec("1.500"), dec("2")].into();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_comparison() {
+        assert!(dec("1.5") > any(1));
+        assert!(dec("1.5") < any(1.75));
+        // 0.1_f32 is slightly above 0.1
+        assert!(dec("0.1") < any(0.1_f32));
+        assert!(dec("1") >= any(1.0));
+        assert!(dec("1.5") < Any::from(2_i128));
+        assert!(dec("-1.5") < Any::from(-1_i128));
+        assert!(dec("1e20") < any(f64::MAX));
+        assert!(dec("-0.5") == any(-0.5_f32));
+        assert!(dec("0.1000000000000000055511151231") != any(0.1));
+        assert_eq!(dec("1").partial_cmp(&any(f64::NAN)), None);
+
+        let mut values = vec![dec("2.5"), any(2), dec("0.5"), any(1.0)];
+        values.sort_by(|a, b| a.cmp_total(b));
+        assert_eq!(values, vec![dec("0.5"), any(1.0), any(2), dec("2.5")]);
+    }
+}
@@ -0,0 +1,693 @@
+//! JSON parse and stringify (JSON.parse / JSON.stringify)
+
+use std::fmt::Display;
+
+use crate::{_null, value::Value, Any, Array, Map};
+
+/// error returned by [`Any::parse_json`]
+///
+/// `line` and `column` are 1-based and point at the offending character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at line {} column {}",
+            self.message, self.line, self.column
+        )
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+/// JSON operations
+impl Any {
+    /// Parses a JSON text, like `JSON.parse`.
+    ///
+    /// Integers that fit in i64 become i64, all other numbers become f64.
+    /// Arrays and objects may be nested at most 128 levels deep; deeper input is an error.
+    /**
+    ```
+    use anyrust::*;
+
+    let value = Any::parse_json(r#"{"name": "John", "age": 20}"#).unwrap();
+    assert_eq!(value.get("name"), any("John"));
+    assert_eq!(value.get("age"), any(20_i64));
+
+    let error = Any::parse_json("[1, 2,\n  ]").unwrap_err();
+    assert_eq!((error.line, error.column), (2, 3));
+    ```
+    */
+    pub fn parse_json(text: &str) -> Result<Any, JsonError> {
+        let mut parser = Parser::new(text);
+
+        parser.skip_whitespace();
+        let value = parser.parse_value()?;
+        parser.skip_whitespace();
+
+        match parser.peek() {
+            None => Ok(value),
+            Some(c) => Err(parser.error(format!("Unexpected token '{}'", c))),
+        }
+    }
+
+    /// Converts the value to a JSON string, like `JSON.stringify`.
+    ///
+    /// Functions are skipped inside maps and become `null` elsewhere. NaN and infinities become `null`.
+    /**
+    ```
+    use anyrust::*;
+
+    let value = array![1, "two", 3.5, null.clone()];
+    assert_eq!(value.to_json(), r#"[1,"two",3.5,null]"#);
+    ```
+    */
+    pub fn to_json(&self) -> String {
+        let mut result = String::new();
+        write_json(&mut result, self, 0, 0);
+        result
+    }
+
+    /// Converts the value to an indented JSON string, like `JSON.stringify(value, null, indent)`.
+    ///
+    /// An indent of 0 is the same as [`Any::to_json`].
+    /**
+    ```
+    use anyrust::*;
+
+    let value = array![1, 2];
+    assert_eq!(value.to_json_pretty(2), "[\n  1,\n  2\n]");
+    ```
+    */
+    pub fn to_json_pretty(&self, indent: usize) -> String {
+        let mut result = String::new();
+        write_json(&mut result, self, indent, 0);
+        result
+    }
+}
+
+fn write_json(result: &mut String, value: &Any, indent: usize, depth: usize) {
+    match &value.value {
+        Value::Null | Value::Undefined | Value::Function(_) => result.push_str("null"),
+        // JSON.stringify throws for a BigInt, but the digits are a valid JSON number
+        Value::Bool(_) | Value::Int(_) | Value::BigInt(_) | Value::Decimal(_) => {
+            result.push_str(&value.to_str())
+        }
+        Value::Float(_) => {
+            let float = value.to_float();
+            if float.is_finite() {
+                result.push_str(&float.to_string());
+            } else {
+                result.push_str("null");
+            }
+        }
+        Value::Array(array) => write_array(result, array, indent, depth),
+        Value::Map(map) => write_map(result, map, indent, depth),
+        // JSON.stringify writes a Set as {}, but its values are more useful
+        Value::Set(_) => write_array(result, &value.to_array(), indent, depth),
+        Value::Pair(pair) => {
+            let (key, value) = pair.to_tuple();
+            write_array(result, &Array(vec![key, value]), indent, depth);
+        }
+        _ => write_string(result, &value.to_str()),
+    }
+}
+
+fn write_array(result: &mut String, array: &Array, indent: usize, depth: usize) {
+    if array.is_empty() {
+        result.push_str("[]");
+        return;
+    }
+
+    result.push('[');
+    for (i, item) in array.0.iter().enumerate() {
+        if i > 0 {
+            result.push(',');
+        }
+        write_newline(result, indent, depth + 1);
+        write_json(result, item, indent, depth + 1);
+    }
+    write_newline(result, indent, depth);
+    result.push(']');
+}
+
+fn write_map(result: &mut String, map: &Map, indent: usize, depth: usize) {
+    let entries = map
+        .0
+        .iter()
+        .filter(|(_, value)| !value.is_function() && !value.is_undefined())
+        .collect::<Vec<_>>();
+
+    if entries.is_empty() {
+        result.push_str("{}");
+        return;
+    }
+
+    result.push('{');
+    for (i, (key, value)) in entries.into_iter().enumerate() {
+        if i > 0 {
+            result.push(',');
+        }
+        write_newline(result, indent, depth + 1);
+        write_string(result, &key.to_str());
+        result.push(':');
+        if indent > 0 {
+            result.push(' ');
+        }
+        write_json(result, value, indent, depth + 1);
+    }
+    write_newline(result, indent, depth);
+    result.push('}');
+}
+
+fn write_newline(result: &mut String, indent: usize, depth: usize) {
+    if indent > 0 {
+        result.push('\n');
+        result.push_str(&" ".repeat(indent * depth));
+    }
+}
+
+fn write_string(result: &mut String, value: &str) {
+    result.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            '\u{08}' => result.push_str("\\b"),
+            '\u{0c}' => result.push_str("\\f"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+    column: usize,
+    depth: usize,
+}
+
+/// deepest nesting of arrays and objects that parse_json accepts, so that untrusted input cannot overflow the stack
+const MAX_DEPTH: usize = 128;
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            chars: text.chars().peekable(),
+            line: 1,
+            column: 1,
+            depth: 0,
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> JsonError {
+        JsonError {
+            message: message.into(),
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    fn unexpected(&mut self) -> JsonError {
+        match self.peek() {
+            Some(c) => self.error(format!("Unexpected token '{}'", c)),
+            None => self.error("Unexpected end of JSON input"),
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), JsonError> {
+        if self.peek() == Some(expected) {
+            self.next();
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(' ' | '\t' | '\n' | '\r') = self.peek() {
+            self.next();
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Any, JsonError> {
+        match self.peek() {
+            Some('{') => self.nested(Self::parse_object),
+            Some('[') => self.nested(Self::parse_array),
+            Some('"') => self.parse_string().map(Any::from),
+            Some('t') => self.parse_keyword("true", Any::from(true)),
+            Some('f') => self.parse_keyword("false", Any::from(false)),
+            Some('n') => self.parse_keyword("null", Any::from(_null)),
+            Some('-' | '0'..='9') => self.parse_number(),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn parse_keyword(&mut self, keyword: &str, value: Any) -> Result<Any, JsonError> {
+        for expected in keyword.chars() {
+            self.expect(expected)?;
+        }
+        Ok(value)
+    }
+
+    fn parse_number(&mut self) -> Result<Any, JsonError> {
+        let mut text = String::new();
+        let mut is_float = false;
+
+        if self.peek() == Some('-') {
+            text.extend(self.next());
+        }
+
+        match self.peek() {
+            Some('0') => text.extend(self.next()),
+            Some('1'..='9') => self.take_digits(&mut text),
+            _ => return Err(self.unexpected()),
+        }
+
+        if self.peek() == Some('.') {
+            is_float = true;
+            text.extend(self.next());
+            if !matches!(self.peek(), Some('0'..='9')) {
+                return Err(self.unexpected());
+            }
+            self.take_digits(&mut text);
+        }
+
+        if let Some('e' | 'E') = self.peek() {
+            is_float = true;
+            text.extend(self.next());
+            if let Some('+' | '-') = self.peek() {
+                text.extend(self.next());
+            }
+            if !matches!(self.peek(), Some('0'..='9')) {
+                return Err(self.unexpected());
+            }
+            self.take_digits(&mut text);
+        }
+
+        if !is_float {
+            if let Ok(integer) = text.parse::<i64>() {
+                return Ok(Any::from(integer));
+            }
+        }
+
+        match text.parse::<f64>() {
+            Ok(float) => Ok(Any::from(float)),
+            Err(_) => Err(self.error(format!("Invalid number '{}'", text))),
+        }
+    }
+
+    fn take_digits(&mut self, text: &mut String) {
+        while let Some('0'..='9') = self.peek() {
+            text.extend(self.next());
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, JsonError> {
+        self.expect('"')?;
+
+        let mut result = String::new();
+        loop {
+            match self.peek() {
+                Some('"') => {
+                    self.next();
+                    return Ok(result);
+                }
+                Some('\\') => {
+                    self.next();
+                    let c = self.parse_escape()?;
+                    result.push(c);
+                }
+                Some(c) if (c as u32) < 0x20 => {
+                    return Err(self.error("Bad control character in string literal"));
+                }
+                Some(c) => {
+                    self.next();
+                    result.push(c);
+                }
+                None => return Err(self.error("Unterminated string in JSON")),
+            }
+        }
+    }
+
+    fn parse_escape(&mut self) -> Result<char, JsonError> {
+        let c = match self.peek() {
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some('/') => '/',
+            Some('b') => '\u{08}',
+            Some('f') => '\u{0c}',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('u') => {
+                self.next();
+                let high = self.parse_hex4()?;
+
+                if !(0xD800..0xDC00).contains(&high) {
+                    return char::from_u32(high)
+                        .ok_or_else(|| self.error("Lone surrogate in string literal"));
+                }
+
+                // surrogate pair
+                self.expect('\\')?;
+                self.expect('u')?;
+                let low = self.parse_hex4()?;
+                if !(0xDC00..0xE000).contains(&low) {
+                    return Err(self.error("Lone surrogate in string literal"));
+                }
+
+                let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                return char::from_u32(code)
+                    .ok_or_else(|| self.error("Invalid unicode escape in string literal"));
+            }
+            _ => return Err(self.error("Bad escaped character in string literal")),
+        };
+
+        self.next();
+        Ok(c)
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, JsonError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            match self.peek().and_then(|c| c.to_digit(16)) {
+                Some(digit) => {
+                    self.next();
+                    code = code * 16 + digit;
+                }
+                None => return Err(self.error("Bad Unicode escape in string literal")),
+            }
+        }
+        Ok(code)
+    }
+
+    /// Parses an array or an object, one level deeper.
+    fn nested(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<Any, JsonError>,
+    ) -> Result<Any, JsonError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(self.error("Maximum nesting depth exceeded"));
+        }
+
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn parse_array(&mut self) -> Result<Any, JsonError> {
+        self.expect('[')?;
+        self.skip_whitespace();
+
+        let mut array = Array::new();
+        if self.peek() == Some(']') {
+            self.next();
+            return Ok(Any::from(array));
+        }
+
+        loop {
+            self.skip_whitespace();
+            array.push(self.parse_value()?);
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(',') => {
+                    self.next();
+                }
+                Some(']') => {
+                    self.next();
+                    return Ok(Any::from(array));
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Any, JsonError> {
+        self.expect('{')?;
+        self.skip_whitespace();
+
+        let mut map = Map::new();
+        if self.peek() == Some('}') {
+            self.next();
+            return Ok(Any::from(map));
+        }
+
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('"') {
+                return Err(self.unexpected());
+            }
+            let key = self.parse_string()?;
+
+            self.skip_whitespace();
+            self.expect(':')?;
+            self.skip_whitespace();
+
+            let value = self.parse_value()?;
+            map.set(key, value);
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(',') => {
+                    self.next();
+                }
+                Some('}') => {
+                    self.next();
+                    return Ok(Any::from(map));
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_json {
+    use super::*;
+    use crate as anyrust;
+    use crate::{any, array, function, map, null, pair, set, undefined};
+
+    #[test]
+    fn test_parse_json() {
+        struct TestCase {
+            name: String,
+            input: &'static str,
+            result: Any,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                name: "integer".to_string(),
+                input: "42",
+                result: Any::new(42_i64),
+            },
+            TestCase {
+                name: "negative float".to_string(),
+                input: "-1.5e2",
+                result: Any::new(-150.0),
+            },
+            TestCase {
+                name: "integer overflow".to_string(),
+                input: "18446744073709551616",
+                result: Any::new(18446744073709551616.0),
+            },
+            TestCase {
+                name: "string".to_string(),
+                input: r#""foo\n\"bar\"""#,
+                result: Any::from("foo\n\"bar\""),
+            },
+            TestCase {
+                name: "non-ascii".to_string(),
+                input: r#""한글 😀""#,
+                result: Any::from("한글 😀"),
+            },
+            TestCase {
+                name: "unicode escape".to_string(),
+                input: r#""\u00e9\ud83d\ude00""#,
+                result: Any::from("é😀"),
+            },
+            TestCase {
+                name: "keywords".to_string(),
+                input: "[true, false, null]",
+                result: array![true, false, _null],
+            },
+            TestCase {
+                name: "nested".to_string(),
+                input: r#" { "a": [1, {"b": []}], "c": {} } "#,
+                result: map! {
+                    "a" => array![1_i64, map! { "b" => array![] }],
+                    "c" => map! {},
+                },
+            },
+        ];
+
+        for test_case in test_cases {
+            let result = Any::parse_json(test_case.input).unwrap();
+            assert_eq!(result, test_case.result, "TC: {}", test_case.name);
+        }
+    }
+
+    #[test]
+    fn test_parse_json_depth() {
+        let error = Any::parse_json(&"[".repeat(1_000_000)).unwrap_err();
+        assert_eq!(error.message, "Maximum nesting depth exceeded");
+        assert_eq!(error.column, MAX_DEPTH + 1);
+
+        let error = Any::parse_json(&"{\"a\":".repeat(MAX_DEPTH + 1)).unwrap_err();
+        assert_eq!(error.message, "Maximum nesting depth exceeded");
+
+        let text = "[".repeat(MAX_DEPTH) + &"]".repeat(MAX_DEPTH);
+        assert!(Any::parse_json(&text).is_ok());
+    }
+
+    #[test]
+    fn test_parse_json_error() {
+        struct TestCase {
+            input: &'static str,
+            line: usize,
+            column: usize,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                input: "",
+                line: 1,
+                column: 1,
+            },
+            TestCase {
+                input: "[1, 2",
+                line: 1,
+                column: 6,
+            },
+            TestCase {
+                input: "{\n  \"a\": 1,\n  b: 2\n}",
+                line: 3,
+                column: 3,
+            },
+            TestCase {
+                input: "[01]",
+                line: 1,
+                column: 3,
+            },
+            TestCase {
+                input: "\"abc",
+                line: 1,
+                column: 5,
+            },
+            TestCase {
+                input: "nul",
+                line: 1,
+                column: 4,
+            },
+            TestCase {
+                input: "1 2",
+                line: 1,
+                column: 3,
+            },
+        ];
+
+        for test_case in test_cases {
+            let error = Any::parse_json(test_case.input).unwrap_err();
+            assert_eq!(
+                (error.line, error.column),
+                (test_case.line, test_case.column),
+                "TC: {:?} => {}",
+                test_case.input,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn test_to_json() {
+        assert_eq!(any(5).to_json(), "5");
+        assert_eq!(any(1.5).to_json(), "1.5");
+        assert_eq!(Any::from(u128::MAX).to_json(), u128::MAX.to_string());
+        assert_eq!(Any::parse_decimal("1.50").unwrap().to_json(), "1.50");
+        assert_eq!(any(f64::NAN).to_json(), "null");
+        assert_eq!(any(true).to_json(), "true");
+        assert_eq!(any(_null).to_json(), "null");
+        assert_eq!(any("a\"b\\c\n\u{1}").to_json(), r#""a\"b\\c\n\u0001""#);
+        assert_eq!(pair!(1, "one").to_json(), r#"[1,"one"]"#);
+        assert_eq!(map! { "key" => "value" }.to_json(), r#"{"key":"value"}"#);
+        assert_eq!(map! { 1 => 2 }.to_json(), r#"{"1":2}"#);
+        assert_eq!(set![1, "a", 1].to_json(), r#"[1,"a"]"#);
+    }
+
+    #[test]
+    fn test_json_key_order() {
+        let value = map! { "name" => "John", "age" => 20, "adult" => true };
+        assert_eq!(value.to_json(), r#"{"name":"John","age":20,"adult":true}"#);
+
+        let json = r#"{"z":1,"a":2,"m":3,"a":4}"#;
+        assert_eq!(
+            Any::parse_json(json).unwrap().to_json(),
+            r#"{"z":1,"a":4,"m":3}"#
+        );
+    }
+
+    #[test]
+    fn test_to_json_function() {
+        let f = function!(x => { x });
+
+        assert_eq!(f.to_json(), "null");
+        assert_eq!(array![1, f.clone()].to_json(), "[1,null]");
+        assert_eq!(map! { "f" => f }.to_json(), "{}");
+    }
+
+    #[test]
+    fn test_to_json_undefined() {
+        assert_eq!(undefined.to_json(), "null");
+        assert_eq!(array![1, undefined.clone()].to_json(), "[1,null]");
+        assert_eq!(
+            map! { "a" => undefined.clone(), "b" => null.clone() }.to_json(),
+            "{\"b\":null}"
+        );
+    }
+
+    #[test]
+    fn test_to_json_pretty() {
+        let value = map! { "a" => array![1, array![]], };
+        assert_eq!(
+            value.to_json_pretty(2),
+            "{\n  \"a\": [\n    1,\n    []\n  ]\n}"
+        );
+        assert_eq!(value.to_json_pretty(0), value.to_json());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let value = array![1, "two", 3.5, true, _null, map! { "a\tb" => array!["😀"] }];
+        let parsed = Any::parse_json(&value.to_json()).unwrap();
+        assert_eq!(parsed.to_json(), value.to_json());
+    }
+}
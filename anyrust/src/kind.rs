@@ -0,0 +1,242 @@
+//! type introspection, like JavaScript `typeof`
+
+use crate::{
+    value::{Float, Int, Value},
+    Any,
+};
+
+/// concrete type of a value stored in Any
+///
+/// Integers and floats keep their exact width, so a value can be matched on without chaining `is_*` checks.
+/**
+```
+use anyrust::*;
+
+let describe = |value: &Any| match value.kind() {
+    AnyKind::U8 | AnyKind::U16 | AnyKind::U32 | AnyKind::U64 | AnyKind::Usize => "unsigned",
+    AnyKind::F32 | AnyKind::F64 => "float",
+    AnyKind::Str => "string",
+    _ => "other",
+};
+
+assert_eq!(describe(&any(5_u8)), "unsigned");
+assert_eq!(describe(&any(1.5)), "float");
+assert_eq!(describe(&any("a")), "string");
+assert_eq!(describe(&any(-1)), "other");
+```
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnyKind {
+    I8,
+    I16,
+    I32,
+    I64,
+    Isize,
+    U8,
+    U16,
+    U32,
+    U64,
+    Usize,
+    F32,
+    F64,
+    BigInt,
+    Decimal,
+    Str,
+    Bool,
+    Array,
+    Map,
+    Set,
+    Pair,
+    Function,
+    Null,
+    Undefined,
+    /// user type, like one stored with `Any::object`
+    Object,
+}
+
+/// Implements type introspection.
+impl Any {
+    /// Returns the type of the value as a JavaScript `typeof` string.
+    ///
+    /// Integers, floats and Decimals are `"number"`, and a BigInt is `"bigint"`.
+    /// Like JavaScript, null is `"object"`, as are arrays, maps, sets, pairs and user types.
+    /// ```
+    /// use anyrust::*;
+    ///
+    /// assert_eq!(any(5_u8).type_of(), "number");
+    /// assert_eq!(any("a").type_of(), "string");
+    /// assert_eq!(array![1].type_of(), "object");
+    /// assert_eq!(null.type_of(), "object");
+    /// assert_eq!(undefined.type_of(), "undefined");
+    /// ```
+    pub fn type_of(&self) -> &'static str {
+        match &self.value {
+            Value::Int(_) | Value::Float(_) | Value::Decimal(_) => "number",
+            Value::BigInt(_) => "bigint",
+            Value::Str(_) => "string",
+            Value::Bool(_) => "boolean",
+            Value::Function(_) => "function",
+            Value::Undefined => "undefined",
+            Value::Array(_)
+            | Value::Map(_)
+            | Value::Set(_)
+            | Value::Pair(_)
+            | Value::Null
+            | Value::Custom(_) => "object",
+        }
+    }
+
+    /// Returns the concrete type of the value.
+    pub fn kind(&self) -> AnyKind {
+        match &self.value {
+            Value::Int(int) => match int {
+                Int::I8(_) => AnyKind::I8,
+                Int::I16(_) => AnyKind::I16,
+                Int::I32(_) => AnyKind::I32,
+                Int::I64(_) => AnyKind::I64,
+                Int::Isize(_) => AnyKind::Isize,
+                Int::U8(_) => AnyKind::U8,
+                Int::U16(_) => AnyKind::U16,
+                Int::U32(_) => AnyKind::U32,
+                Int::U64(_) => AnyKind::U64,
+                Int::Usize(_) => AnyKind::Usize,
+            },
+            Value::Float(Float::F32(_)) => AnyKind::F32,
+            Value::Float(Float::F64(_)) => AnyKind::F64,
+            Value::BigInt(_) => AnyKind::BigInt,
+            Value::Decimal(_) => AnyKind::Decimal,
+            Value::Str(_) => AnyKind::Str,
+            Value::Bool(_) => AnyKind::Bool,
+            Value::Array(_) => AnyKind::Array,
+            Value::Map(_) => AnyKind::Map,
+            Value::Set(_) => AnyKind::Set,
+            Value::Pair(_) => AnyKind::Pair,
+            Value::Function(_) => AnyKind::Function,
+            Value::Null => AnyKind::Null,
+            Value::Undefined => AnyKind::Undefined,
+            Value::Custom(_) => AnyKind::Object,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_kind {
+    use super::*;
+    use crate as anyrust;
+    use crate::{_null, _undefined, any, array, function, map, pair, set, Decimal};
+
+    #[derive(Debug, Clone)]
+    struct Point;
+
+    #[test]
+    fn test_type_of() {
+        struct TestCase {
+            value: Any,
+            type_of: &'static str,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                value: any(5_i8),
+                type_of: "number",
+            },
+            TestCase {
+                value: any(u64::MAX),
+                type_of: "number",
+            },
+            TestCase {
+                value: any(f32::NAN),
+                type_of: "number",
+            },
+            TestCase {
+                value: any(Decimal::new(15, 1)),
+                type_of: "number",
+            },
+            TestCase {
+                value: any(1_i128),
+                type_of: "bigint",
+            },
+            TestCase {
+                value: any("a"),
+                type_of: "string",
+            },
+            TestCase {
+                value: any(false),
+                type_of: "boolean",
+            },
+            TestCase {
+                value: function!(x => { x }),
+                type_of: "function",
+            },
+            TestCase {
+                value: any(_undefined),
+                type_of: "undefined",
+            },
+            TestCase {
+                value: any(_null),
+                type_of: "object",
+            },
+            TestCase {
+                value: array![1],
+                type_of: "object",
+            },
+            TestCase {
+                value: map! { "a" => 1 },
+                type_of: "object",
+            },
+            TestCase {
+                value: set! { 1 },
+                type_of: "object",
+            },
+            TestCase {
+                value: pair!(1, 2),
+                type_of: "object",
+            },
+            TestCase {
+                value: Any::object(Point),
+                type_of: "object",
+            },
+        ];
+
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.value.type_of(),
+                test_case.type_of,
+                "TC: {}",
+                test_case.value
+            );
+        }
+    }
+
+    #[test]
+    fn test_kind() {
+        assert_eq!(any(1_i8).kind(), AnyKind::I8);
+        assert_eq!(any(1_i16).kind(), AnyKind::I16);
+        assert_eq!(any(1_i32).kind(), AnyKind::I32);
+        assert_eq!(any(1_i64).kind(), AnyKind::I64);
+        assert_eq!(any(1_isize).kind(), AnyKind::Isize);
+        assert_eq!(any(1_u8).kind(), AnyKind::U8);
+        assert_eq!(any(1_u16).kind(), AnyKind::U16);
+        assert_eq!(any(1_u32).kind(), AnyKind::U32);
+        assert_eq!(any(1_u64).kind(), AnyKind::U64);
+        assert_eq!(any(1_usize).kind(), AnyKind::Usize);
+        assert_eq!(any(1_f32).kind(), AnyKind::F32);
+        assert_eq!(any(1_f64).kind(), AnyKind::F64);
+        assert_eq!(any(1_u128).kind(), AnyKind::BigInt);
+        assert_eq!(any(Decimal::ONE).kind(), AnyKind::Decimal);
+        assert_eq!(any("a").kind(), AnyKind::Str);
+        assert_eq!(any(true).kind(), AnyKind::Bool);
+        assert_eq!(array![].kind(), AnyKind::Array);
+        assert_eq!(map! {}.kind(), AnyKind::Map);
+        assert_eq!(set! {}.kind(), AnyKind::Set);
+        assert_eq!(pair!(1, 2).kind(), AnyKind::Pair);
+        assert_eq!(function!(x => { x }).kind(), AnyKind::Function);
+        assert_eq!(any(_null).kind(), AnyKind::Null);
+        assert_eq!(any(_undefined).kind(), AnyKind::Undefined);
+        assert_eq!(Any::object(Point).kind(), AnyKind::Object);
+
+        // arithmetic keeps the operand type
+        assert_eq!((any(200_u8) + any(1_u8)).kind(), AnyKind::U8);
+        assert_eq!((any(200_u8) + any(100_u8)).kind(), AnyKind::F64);
+    }
+}
@@ -4,18 +4,50 @@
 
 use std::{
     any::TypeId,
-    borrow::BorrowMut,
+    borrow::Cow,
+    cmp::Ordering,
     collections::HashMap,
     fmt::{Debug, Display},
     hash::Hash,
     ops::{
-        Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Not, Shl, ShlAssign,
-        Shr, ShrAssign, Sub, SubAssign,
+        Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div,
+        DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Not, Rem, RemAssign, Shl, ShlAssign, Shr,
+        ShrAssign, Sub, SubAssign,
     },
-    rc::Rc,
+    sync::Arc,
 };
 
 use dyn_clone::{clone_trait_object, DynClone};
+use indexmap::IndexMap;
+use num_traits::{FromPrimitive, ToPrimitive};
+
+mod array;
+mod bigint;
+mod convert;
+mod decimal;
+mod json;
+mod kind;
+mod mixed_ops;
+mod object;
+mod path;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod set;
+mod string;
+mod value;
+
+#[cfg(feature = "derive")]
+pub use anyrust_macro::{FromAny, IntoAny};
+pub use array::{Callback, Comparator, Reducer};
+pub use bigint::MAX_BIGINT_BITS;
+pub use convert::{FromAny, IntoAny};
+pub use json::JsonError;
+pub use kind::AnyKind;
+pub use num_bigint::BigInt;
+pub use object::Object;
+pub use rust_decimal::{Decimal, RoundingStrategy};
+pub use set::Set;
+use value::{Int, Value};
 
 /// shortcut function for creating any value
 pub fn any(value: impl Into<Any>) -> Any {
@@ -44,9 +76,22 @@ pub struct Null;
 #[allow(non_upper_case_globals)]
 pub(crate) const _null: Null = Null {};
 
+/// type of undefined value
+///
+/// Undefined means that a value is absent, like a missing key or an index out of range,
+/// where null is a value that was set explicitly.
+#[derive(Debug, Clone, Copy)]
+pub struct Undefined;
+
+/// undefined value
+#[allow(non_upper_case_globals)]
+pub(crate) const _undefined: Undefined = Undefined {};
+
 /// function type
+///
+/// The closure is shared through an `Arc`, so cloned functions can be sent to and called from other threads.
 pub struct Function {
-    f: Rc<dyn Fn(Any) -> Any>,
+    f: Arc<dyn Fn(Any) -> Any + Send + Sync>,
     args_count: usize,
 }
 
@@ -56,10 +101,6 @@ impl Debug for Function {
     }
 }
 
-unsafe impl Send for Function {}
-
-unsafe impl Sync for Function {}
-
 impl Clone for Function {
     fn clone(&self) -> Self {
         Self {
@@ -69,19 +110,31 @@ impl Clone for Function {
     }
 }
 
+/// Functions are equal only to themselves and their clones, like JavaScript functions.
+impl PartialEq for Function {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.f, &other.f)
+    }
+}
+
+impl Eq for Function {}
+
+impl Hash for Function {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        (Arc::as_ptr(&self.f) as *const () as usize).hash(state);
+    }
+}
+
 impl Function {
     pub fn new(f: impl Fn(Any) -> Any + 'static + Send + Sync, args_count: usize) -> Self {
         Self {
-            f: Rc::new(f),
+            f: Arc::new(f),
             args_count,
         }
     }
 
     pub fn call(&self, args: Any) -> Any {
-        let mut rc = self.f.clone();
-        let borrowed = rc.borrow_mut();
-
-        borrowed(args)
+        (self.f)(args)
     }
 
     pub fn composite(&self, other: Self) -> Self {
@@ -92,7 +145,7 @@ impl Function {
         let args_count = self.args_count;
 
         Self {
-            f: Rc::new(move |args| {
+            f: Arc::new(move |args| {
                 let result = f(args.clone());
                 other_f(params![result])
             }),
@@ -120,10 +173,10 @@ mod test_function {
         );
 
         let result = f.call(array![1, 2, 3, 4, 5]);
-        assert_eq!(result, Any::from(15_i64));
+        assert_eq!(result, Any::from(15));
 
         let result = f.call(array![1, 2, 3, 4, 5, 7]);
-        assert_eq!(result, Any::from(22_i64));
+        assert_eq!(result, Any::from(22));
     }
 
     #[test]
@@ -146,12 +199,12 @@ mod test_function {
         );
 
         let add_result = add.call(array![1, 2]);
-        assert_eq!(add_result, Any::from(3_i64), "add_result: {:?}", add_result);
+        assert_eq!(add_result, Any::from(3), "add_result: {:?}", add_result);
 
         let negative_result = negative.call(array![add_result]);
         assert_eq!(
             negative_result,
-            Any::from(-3_i64),
+            Any::from(-3),
             "negative_result: {:?}",
             negative_result
         );
@@ -159,7 +212,72 @@ mod test_function {
         let composited = add.composite(negative);
 
         let result = composited.call(array![1, 2]);
-        assert_eq!(result, Any::from(-3_i64), "result: {:?}", result);
+        assert_eq!(result, Any::from(-3), "result: {:?}", result);
+    }
+
+    #[test]
+    fn test_function_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<Function>();
+        assert_send_sync::<Any>();
+    }
+
+    #[test]
+    fn test_share_function_across_threads() {
+        let add = function!(lhs, rhs => {
+            lhs + rhs
+        });
+        let negative = function!(num => {
+            num * any(-1)
+        });
+        let composite = add.clone() >> negative;
+
+        std::thread::scope(|scope| {
+            let handles = (0..8_i64)
+                .map(|i| {
+                    let add = add.clone();
+                    let composite = &composite;
+                    scope.spawn(move || {
+                        for _ in 0..100 {
+                            assert_eq!(add.call(params![i, 1_i64]), Any::from(i + 1));
+                            assert_eq!(composite.call(params![i, 1_i64]), Any::from(-(i + 1)));
+                        }
+                        add.call(params![i, i])
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            for (i, handle) in handles.into_iter().enumerate() {
+                assert_eq!(handle.join().unwrap(), Any::from(i as i64 * 2));
+            }
+        });
+    }
+
+    #[test]
+    fn test_send_function_to_thread() {
+        let counter = Arc::new(std::sync::atomic::AtomicI64::new(0));
+        let shared = counter.clone();
+        let increment = Function::new(
+            move |args| {
+                let delta = args[0].to_integer();
+                Any::from(shared.fetch_add(delta, std::sync::atomic::Ordering::SeqCst) + delta)
+            },
+            1,
+        );
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                let increment = increment.clone();
+                scope.spawn(move || {
+                    for _ in 0..250 {
+                        increment.call(params![1]);
+                    }
+                });
+            }
+        });
+
+        assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 1000);
     }
 }
 
@@ -314,7 +432,7 @@ mod test_array {
 }
 
 /// Any Tuple type
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Pair((Any, Any));
 
 impl Pair {
@@ -328,8 +446,10 @@ impl Pair {
 }
 
 /// key-value map type
+///
+/// Keys keep their insertion order, like the keys of a JavaScript object.
 #[derive(Debug, Clone)]
-pub struct Map(std::collections::HashMap<Any, Any>);
+pub struct Map(IndexMap<Any, Any>);
 
 impl Default for Map {
     fn default() -> Self {
@@ -339,15 +459,21 @@ impl Default for Map {
 
 impl Map {
     pub fn new() -> Self {
-        Self(HashMap::new())
+        Self(IndexMap::new())
     }
 
+    /// Sets a value for the key.
+    ///
+    /// A new key is appended at the end. Overwriting an existing key keeps its position.
     pub fn set(&mut self, key: impl Into<Any>, value: impl Into<Any>) {
         self.0.insert(key.into(), value.into());
     }
 
+    /// Removes the key and returns its value.
+    ///
+    /// The remaining keys keep their order. If the key is set again later, it is appended at the end.
     pub fn delete(&mut self, key: &Any) -> Option<Any> {
-        self.0.remove(key)
+        self.0.shift_remove(key)
     }
 
     pub fn get(&self, key: &Any) -> Option<&Any> {
@@ -422,6 +548,37 @@ mod test_map {
         assert_eq!(m.length(), 2);
     }
 
+    #[test]
+    fn test_insertion_order() {
+        let mut m = Map::new();
+        m.set("name", "John");
+        m.set("age", 20);
+        m.set("city", "Seoul");
+        assert_eq!(m.to_str(), "{name: John, age: 20, city: Seoul}");
+
+        // overwriting keeps the position
+        m.set("name", "Jane");
+        assert_eq!(m.to_str(), "{name: Jane, age: 20, city: Seoul}");
+
+        // deleting keeps the order of the rest, and a re-inserted key goes to the end
+        m.delete(&Any::new("name"));
+        assert_eq!(m.to_str(), "{age: 20, city: Seoul}");
+        m.set("name", "John");
+        assert_eq!(m.to_str(), "{age: 20, city: Seoul, name: John}");
+
+        let keys = m.into_iter().map(|(k, _)| k).collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![Any::new("age"), Any::new("city"), Any::new("name")]
+        );
+    }
+
+    #[test]
+    fn test_from_iter() {
+        let m: Map = vec![("b", 1), ("a", 2)].into_iter().collect();
+        assert_eq!(m.to_str(), "{b: 1, a: 2}");
+    }
+
     #[test]
     fn test_is_empty() {
         let mut m = Map::new();
@@ -487,7 +644,7 @@ pub trait ToMap {
 /// Trait for casting: Defines how to convert when cast to a Pair.
 pub trait ToPair {
     fn to_pair(&self) -> Pair {
-        Pair::new(null.clone(), null.clone())
+        Pair::new(undefined.clone(), undefined.clone())
     }
 }
 
@@ -499,13 +656,136 @@ pub trait ToBoolean {
 /// Trait for casting: Defines how to convert when cast to a function.
 pub trait ToFunction {
     fn to_function(&self) -> Function {
-        Function::new(|_| Any::from(_null), 0)
+        Function::new(|_| Any::from(_undefined), 0)
+    }
+}
+
+/// error returned by the fallible conversion methods (`try_to_integer`, `try_to_float`, ...)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CastError {
+    /// type name of the source value
+    pub from: &'static str,
+    /// name of the requested type
+    pub to: &'static str,
+    /// why the conversion failed
+    pub kind: CastErrorKind,
+    /// where the conversion failed inside the value, like `shapes.0.width`, in the syntax of [`Any::path`]
+    ///
+    /// Set by `#[derive(FromAny)]` and by the conversions of collections.
+    pub path: Option<String>,
+}
+
+/// reason of a [`CastError`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CastErrorKind {
+    /// the value has no meaningful conversion to the requested type
+    Type,
+    /// the tag of an enum does not name any variant
+    UnknownVariant,
+    /// the map of an enum does not have exactly one key
+    AmbiguousVariant,
+}
+
+impl CastError {
+    pub fn new(from: &'static str, to: &'static str) -> Self {
+        Self {
+            from,
+            to,
+            kind: CastErrorKind::Type,
+            path: None,
+        }
+    }
+
+    /// Error for an enum tag that names no variant of `to`.
+    pub fn unknown_variant(tag: &Any, to: &'static str) -> Self {
+        Self {
+            kind: CastErrorKind::UnknownVariant,
+            ..Self::new(tag.type_name(), to)
+        }
+    }
+
+    /// Error for an enum map that does not have exactly one key.
+    pub fn ambiguous_variant(to: &'static str) -> Self {
+        Self {
+            kind: CastErrorKind::AmbiguousVariant,
+            ..Self::new("Map", to)
+        }
+    }
+
+    /// Adds a key or index in front of the path, as the error goes out of a nested conversion.
+    pub fn with_path(self, segment: impl Display) -> Self {
+        let path = match self.path {
+            Some(path) => format!("{segment}.{path}"),
+            None => segment.to_string(),
+        };
+
+        Self {
+            path: Some(path),
+            ..self
+        }
+    }
+}
+
+impl Display for CastError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            CastErrorKind::Type => write!(f, "cannot cast {} to {}", self.from, self.to)?,
+            CastErrorKind::UnknownVariant => write!(f, "unknown variant of {}", self.to)?,
+            CastErrorKind::AmbiguousVariant => {
+                write!(f, "expected a map with exactly one key for {}", self.to)?
+            }
+        }
+
+        match &self.path {
+            Some(path) => write!(f, " at {path}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::error::Error for CastError {}
+
+/// Parses a numeric string like `Number()` does for integers. (surrounding whitespace is ignored)
+fn parse_integer(text: &str) -> Option<i64> {
+    let text = text.trim();
+
+    match text.parse::<i64>() {
+        Ok(integer) => Some(integer),
+        Err(_) => float_to_integer(parse_float(text)?),
+    }
+}
+
+/// Parses a numeric string like `Number()` does. (surrounding whitespace is ignored)
+///
+/// Rust also accepts `nan`, `inf` and `infinity` in any case, but JavaScript only accepts `Infinity` with an optional sign.
+fn parse_float(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let unsigned = text.strip_prefix(['+', '-']).unwrap_or(text);
+
+    match unsigned {
+        "Infinity" if text.starts_with('-') => Some(f64::NEG_INFINITY),
+        "Infinity" => Some(f64::INFINITY),
+        _ if unsigned.starts_with(|c: char| c.is_ascii_digit() || c == '.') => text.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Truncates the float to an integer. Returns None if it is NaN or out of range.
+fn float_to_integer(float: f64) -> Option<i64> {
+    if float.is_finite() && float >= i64::MIN as f64 && float < i64::MAX as f64 {
+        Some(float as i64)
+    } else {
+        None
     }
 }
 
+/// BigInts are written with the `n` suffix, like a JavaScript literal.
 impl Display for Any {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.data)
+        match &self.value {
+            Value::BigInt(bigint) => write!(f, "{bigint}n"),
+            value => write!(f, "{}", value.as_anyable()),
+        }
     }
 }
 
@@ -569,7 +849,7 @@ impl ToArray for Array {
 
 impl ToMap for Array {
     fn to_map(&self) -> Map {
-        Map(HashMap::new())
+        Map::new()
     }
 }
 
@@ -607,8 +887,8 @@ impl ToStr for Array {
 
 impl ToPair for Array {
     fn to_pair(&self) -> Pair {
-        let lhs = self.0.first().unwrap_or(&null).clone();
-        let rhs = self.0.get(1).unwrap_or(&null).clone();
+        let lhs = self.0.first().unwrap_or(&undefined).clone();
+        let rhs = self.0.get(1).unwrap_or(&undefined).clone();
         Pair::new(lhs, rhs)
     }
 }
@@ -660,7 +940,7 @@ impl ToArray for Pair {
 
 impl ToMap for Pair {
     fn to_map(&self) -> Map {
-        Map(HashMap::new())
+        Map::new()
     }
 }
 
@@ -711,7 +991,7 @@ impl ToArray for i8 {
 
 impl ToMap for i8 {
     fn to_map(&self) -> Map {
-        Map(HashMap::new())
+        Map::new()
     }
 }
 
@@ -759,7 +1039,7 @@ impl ToArray for i16 {
 
 impl ToMap for i16 {
     fn to_map(&self) -> Map {
-        Map(HashMap::new())
+        Map::new()
     }
 }
 
@@ -807,7 +1087,7 @@ impl ToArray for i32 {
 
 impl ToMap for i32 {
     fn to_map(&self) -> Map {
-        Map(HashMap::new())
+        Map::new()
     }
 }
 
@@ -855,7 +1135,7 @@ impl ToArray for i64 {
 
 impl ToMap for i64 {
     fn to_map(&self) -> Map {
-        Map(HashMap::new())
+        Map::new()
     }
 }
 
@@ -903,7 +1183,7 @@ impl ToArray for isize {
 
 impl ToMap for isize {
     fn to_map(&self) -> Map {
-        Map(HashMap::new())
+        Map::new()
     }
 }
 
@@ -951,7 +1231,7 @@ impl ToArray for u8 {
 
 impl ToMap for u8 {
     fn to_map(&self) -> Map {
-        Map(HashMap::new())
+        Map::new()
     }
 }
 
@@ -999,7 +1279,7 @@ impl ToArray for u16 {
 
 impl ToMap for u16 {
     fn to_map(&self) -> Map {
-        Map(HashMap::new())
+        Map::new()
     }
 }
 
@@ -1047,7 +1327,7 @@ impl ToArray for u32 {
 
 impl ToMap for u32 {
     fn to_map(&self) -> Map {
-        Map(HashMap::new())
+        Map::new()
     }
 }
 
@@ -1069,9 +1349,10 @@ impl From<u64> for Any {
     }
 }
 
+/// Values above i64::MAX saturate.
 impl ToInteger for u64 {
     fn to_integer(&self) -> i64 {
-        *self as i64
+        i64::try_from(*self).unwrap_or(i64::MAX)
     }
 }
 
@@ -1095,7 +1376,7 @@ impl ToArray for u64 {
 
 impl ToMap for u64 {
     fn to_map(&self) -> Map {
-        Map(HashMap::new())
+        Map::new()
     }
 }
 
@@ -1117,9 +1398,10 @@ impl From<usize> for Any {
     }
 }
 
+/// Values above i64::MAX saturate.
 impl ToInteger for usize {
     fn to_integer(&self) -> i64 {
-        *self as i64
+        i64::try_from(*self).unwrap_or(i64::MAX)
     }
 }
 
@@ -1143,7 +1425,7 @@ impl ToArray for usize {
 
 impl ToMap for usize {
     fn to_map(&self) -> Map {
-        Map(HashMap::new())
+        Map::new()
     }
 }
 
@@ -1191,7 +1473,7 @@ impl ToArray for f32 {
 
 impl ToMap for f32 {
     fn to_map(&self) -> Map {
-        Map(HashMap::new())
+        Map::new()
     }
 }
 
@@ -1239,7 +1521,7 @@ impl ToArray for f64 {
 
 impl ToMap for f64 {
     fn to_map(&self) -> Map {
-        Map(HashMap::new())
+        Map::new()
     }
 }
 
@@ -1270,7 +1552,7 @@ impl From<String> for Any {
 
 impl ToInteger for String {
     fn to_integer(&self) -> i64 {
-        self.parse().unwrap()
+        parse_integer(self).unwrap_or(0)
     }
 }
 
@@ -1282,7 +1564,11 @@ impl ToStr for String {
 
 impl ToFloat for String {
     fn to_float(&self) -> f64 {
-        self.parse().unwrap()
+        if self.trim().is_empty() {
+            0.0
+        } else {
+            parse_float(self).unwrap_or(f64::NAN)
+        }
     }
 }
 
@@ -1294,7 +1580,7 @@ impl ToArray for String {
 
 impl ToMap for String {
     fn to_map(&self) -> Map {
-        Map(HashMap::new())
+        Map::new()
     }
 }
 
@@ -1318,7 +1604,7 @@ impl From<&str> for Any {
 
 impl ToInteger for &str {
     fn to_integer(&self) -> i64 {
-        self.parse().unwrap()
+        parse_integer(self).unwrap_or(0)
     }
 }
 
@@ -1330,7 +1616,11 @@ impl ToStr for &str {
 
 impl ToFloat for &str {
     fn to_float(&self) -> f64 {
-        self.parse().unwrap()
+        if self.trim().is_empty() {
+            0.0
+        } else {
+            parse_float(self).unwrap_or(f64::NAN)
+        }
     }
 }
 
@@ -1342,7 +1632,7 @@ impl ToArray for &str {
 
 impl ToMap for &str {
     fn to_map(&self) -> Map {
-        Map(HashMap::new())
+        Map::new()
     }
 }
 
@@ -1398,7 +1688,7 @@ impl ToArray for bool {
 
 impl ToMap for bool {
     fn to_map(&self) -> Map {
-        Map(HashMap::new())
+        Map::new()
     }
 }
 
@@ -1422,18 +1712,60 @@ impl PartialEq for Map {
 
 impl Eq for Map {}
 
+/// Equal maps can have their keys in a different order, so the entries are hashed separately
+/// and combined with an operation that does not depend on the order.
+impl Hash for Map {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let entries = self.0.iter().fold(0_u64, |sum, entry| {
+            // DefaultHasher::new() always uses the same keys, so this is deterministic
+            let mut hasher = std::hash::DefaultHasher::new();
+            entry.hash(&mut hasher);
+            sum.wrapping_add(std::hash::Hasher::finish(&hasher))
+        });
+
+        self.0.len().hash(state);
+        entries.hash(state);
+    }
+}
+
 impl From<HashMap<Any, Any>> for Any {
     fn from(value: HashMap<Any, Any>) -> Self {
-        Any::new(Map(value))
+        Any::new(Map::from(value))
     }
 }
 
 impl From<HashMap<Any, Any>> for Map {
     fn from(value: HashMap<Any, Any>) -> Self {
+        Map(value.into_iter().collect())
+    }
+}
+
+impl From<IndexMap<Any, Any>> for Any {
+    fn from(value: IndexMap<Any, Any>) -> Self {
+        Any::new(Map(value))
+    }
+}
+
+impl From<IndexMap<Any, Any>> for Map {
+    fn from(value: IndexMap<Any, Any>) -> Self {
         Map(value)
     }
 }
 
+impl<K, V> FromIterator<(K, V)> for Map
+where
+    K: Into<Any>,
+    V: Into<Any>,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Map::new();
+        for (key, value) in iter {
+            map.set(key, value);
+        }
+        map
+    }
+}
+
 impl From<Map> for Any {
     fn from(value: Map) -> Self {
         Any::new(value)
@@ -1542,7 +1874,7 @@ impl ToArray for Null {
 
 impl ToMap for Null {
     fn to_map(&self) -> Map {
-        Map(HashMap::new())
+        Map::new()
     }
 }
 
@@ -1563,6 +1895,61 @@ impl ToPair for Null {}
 impl ToFunction for Null {}
 // ---------------
 
+// Undefined 트레잇 구현
+impl From<Undefined> for Any {
+    fn from(value: Undefined) -> Self {
+        Any::new(value)
+    }
+}
+
+impl ToInteger for Undefined {
+    fn to_integer(&self) -> i64 {
+        0_i64
+    }
+}
+
+impl ToStr for Undefined {
+    fn to_str(&self) -> String {
+        String::from("undefined")
+    }
+}
+
+/// Converts to NaN, like `Number(undefined)` in JavaScript.
+impl ToFloat for Undefined {
+    fn to_float(&self) -> f64 {
+        f64::NAN
+    }
+}
+
+impl ToArray for Undefined {
+    fn to_array(&self) -> Array {
+        vec![].into()
+    }
+}
+
+impl ToMap for Undefined {
+    fn to_map(&self) -> Map {
+        Map::new()
+    }
+}
+
+impl ToBoolean for Undefined {
+    fn to_boolean(&self) -> bool {
+        false
+    }
+}
+
+impl Display for Undefined {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "undefined")
+    }
+}
+
+impl ToPair for Undefined {}
+
+impl ToFunction for Undefined {}
+// ---------------
+
 // Function 트레잇 구현
 
 impl From<Function> for Any {
@@ -1597,7 +1984,7 @@ impl ToArray for Function {
 
 impl ToMap for Function {
     fn to_map(&self) -> Map {
-        Map(HashMap::new())
+        Map::new()
     }
 }
 
@@ -1637,19 +2024,9 @@ let result = a + b;
 println!("result: {result}"); // result: 510
 ```
 */
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Any {
-    type_id: std::any::TypeId,
-    data: Box<dyn Anyable>,
-}
-
-impl Clone for Any {
-    fn clone(&self) -> Self {
-        Self {
-            type_id: self.type_id,
-            data: dyn_clone::clone_box(&*self.data),
-        }
-    }
+    value: Value,
 }
 
 /// Implements basic behavior common to all types.
@@ -1660,97 +2037,138 @@ impl Any {
         T: Anyable,
     {
         Self {
-            type_id: std::any::TypeId::of::<T>(),
-            data: Box::new(value),
+            value: Value::new(value),
         }
     }
 
     /// Returns the TypeId of the stored value.
     pub fn type_id(&self) -> TypeId {
-        self.type_id
+        self.value.type_id()
+    }
+
+    /// Returns a reference to the stored value if it is of type T.
+    ///
+    /// Values stored with [`Any::object`] are looked up by their inner type.
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        let value: &dyn std::any::Any = self.value.as_anyable();
+
+        match value.downcast_ref::<T>() {
+            Some(value) => Some(value),
+            None => value.downcast_ref::<Object<T>>().map(|object| &object.0),
+        }
+    }
+
+    /// Returns a mutable reference to the stored value if it is of type T.
+    ///
+    /// A user type is shared between clones, so it is copied first if it has been cloned.
+    pub fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        // checked first, so that a shared object is not copied for nothing
+        let type_id = self.type_id();
+        if type_id != TypeId::of::<T>() && type_id != TypeId::of::<Object<T>>() {
+            return None;
+        }
+
+        let value = self.value.as_any_mut();
+
+        if value.is::<T>() {
+            value.downcast_mut::<T>()
+        } else {
+            value
+                .downcast_mut::<Object<T>>()
+                .map(|object| &mut object.0)
+        }
     }
 
-    // pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
-    //     if self.type_id == std::any::TypeId::of::<T>() {
-    //         unsafe { Some(&*(self.data.as_ref() as *const dyn std::any::Any as *const T)) }
-    //     } else {
-    //         None
-    //     }
-    // }
+    /// Moves the stored value out if it is of type T. Otherwise the Any is returned unchanged.
+    pub fn downcast<T: 'static>(self) -> Result<T, Any> {
+        let type_id = self.type_id();
+        if type_id != TypeId::of::<T>() && type_id != TypeId::of::<Object<T>>() {
+            return Err(self);
+        }
 
-    // pub fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
-    //     if self.type_id == std::any::TypeId::of::<T>() {
-    //         unsafe { Some(&mut *(self.data.as_mut() as *mut dyn std::any::Any as *mut T)) }
-    //     } else {
-    //         None
-    //     }
-    // }
+        match self.value.into_boxed().downcast::<T>() {
+            Ok(value) => Ok(*value),
+            Err(boxed) => match boxed.downcast::<Object<T>>() {
+                Ok(object) => Ok(object.0),
+                Err(_) => unreachable!(),
+            },
+        }
+    }
 }
 
 /// Implements type checking for primitive types.
 impl Any {
     /// Returns true if the type is an integer.
     pub fn is_integer(&self) -> bool {
-        self.type_id == *I8
-            || self.type_id == *I16
-            || self.type_id == *I32
-            || self.type_id == *I64
-            || self.type_id == *U8
-            || self.type_id == *U16
-            || self.type_id == *U32
-            || self.type_id == *U64
-            || self.type_id == *ISIZE
-            || self.type_id == *USIZE
+        matches!(self.value, Value::Int(_))
     }
 
     /// Returns true if the type is a float.
     pub fn is_float(&self) -> bool {
-        self.type_id == *F32 || self.type_id == *F64
+        matches!(self.value, Value::Float(_))
     }
 
     /// Returns true if the type is a number.
     pub fn is_number(&self) -> bool {
-        self.is_integer() || self.is_float()
+        self.is_integer() || self.is_float() || self.is_decimal()
     }
 
     /// Returns true if the type is NaN.
     pub fn is_nan(&self) -> bool {
-        self.is_float() && self.data.to_float().is_nan()
+        match self.value {
+            Value::Float(float) => float.to_f64().is_nan(),
+            _ => false,
+        }
     }
 
     /// Returns true if the type is a string.
     pub fn is_string(&self) -> bool {
-        self.type_id == *STRING || self.type_id == *STR
+        matches!(self.value, Value::Str(_))
     }
 
     /// Returns true if the type is an array.
     pub fn is_array(&self) -> bool {
-        self.type_id == *ARRAY
+        matches!(self.value, Value::Array(_))
     }
 
     /// Returns true if the type is a map.
     pub fn is_map(&self) -> bool {
-        self.type_id == *MAP
+        matches!(self.value, Value::Map(_))
     }
 
     /// Returns true if the type is null.
     pub fn is_null(&self) -> bool {
-        self.type_id == *NULL
+        matches!(self.value, Value::Null)
+    }
+
+    /// Returns true if the type is undefined.
+    pub fn is_undefined(&self) -> bool {
+        matches!(self.value, Value::Undefined)
+    }
+
+    /// Returns true if the type is null or undefined, like `value == null` in JavaScript.
+    pub fn is_nullish(&self) -> bool {
+        matches!(self.value, Value::Null | Value::Undefined)
     }
 
     /// Returns true if the type is a boolean.
     pub fn is_boolean(&self) -> bool {
-        self.type_id == *BOOL
+        matches!(self.value, Value::Bool(_))
     }
 
     /// Returns true if the type is a function.
     pub fn is_function(&self) -> bool {
-        self.type_id == *FUNCTION
+        matches!(self.value, Value::Function(_))
     }
 
     /// Returns true if the type is a pair.
     pub fn is_pair(&self) -> bool {
-        self.type_id == *PAIR
+        matches!(self.value, Value::Pair(_))
+    }
+
+    /// Returns true if the type is a user type. (created with `Any::object` or `Any::new`)
+    pub fn is_object(&self) -> bool {
+        matches!(self.value, Value::Custom(_))
     }
 }
 
@@ -1758,42 +2176,62 @@ impl Any {
 impl Any {
     /// Converts the type to an integer.
     pub fn to_integer(&self) -> i64 {
-        self.data.to_integer()
+        match &self.value {
+            Value::Int(int) => int.to_i64(),
+            Value::Float(float) => float.to_f64() as i64,
+            value => value.as_anyable().to_integer(),
+        }
     }
 
     /// Converts the type to a float.
     pub fn to_float(&self) -> f64 {
-        self.data.to_float()
+        match &self.value {
+            Value::Int(int) => int.to_f64(),
+            Value::Float(float) => float.to_f64(),
+            value => value.as_anyable().to_float(),
+        }
     }
 
     /// Converts the type to a string.
     pub fn to_str(&self) -> String {
-        self.data.to_str()
+        match &self.value {
+            Value::Str(string) => string.clone(),
+            value => value.as_anyable().to_str(),
+        }
     }
 
     /// Converts the type to an array.
     pub fn to_array(&self) -> Array {
-        self.data.to_array()
+        match &self.value {
+            Value::Array(array) => array.clone(),
+            value => value.as_anyable().to_array(),
+        }
     }
 
     /// Converts the type to a map.
     pub fn to_map(&self) -> Map {
-        self.data.to_map()
+        match &self.value {
+            Value::Map(map) => map.as_ref().clone(),
+            value => value.as_anyable().to_map(),
+        }
     }
 
     /// Converts the type to a boolean.
     pub fn to_boolean(&self) -> bool {
-        self.data.to_boolean()
+        match &self.value {
+            Value::Bool(boolean) => *boolean,
+            value => value.as_anyable().to_boolean(),
+        }
     }
 
     /// Converts the type to a pair.
     pub fn to_pair(&self) -> Pair {
-        self.data.to_pair()
+        self.value.as_anyable().to_pair()
     }
 
     /// Converts the type to a function.
     pub fn to_function(&self) -> Function {
-        self.data.to_function()
+        self.value.as_anyable().to_function()
     }
 }
 
@@ -2059,6 +2497,58 @@ mod test_type_check_for_any {
         assert!(!a.is_null());
     }
 
+    #[test]
+    fn test_is_undefined() {
+        let a = Any::new(_undefined);
+        assert!(a.is_undefined());
+        assert!(!a.is_null());
+        assert!(a.is_nullish());
+
+        let a = Any::new(_null);
+        assert!(!a.is_undefined());
+        assert!(a.is_nullish());
+
+        let a = Any::new(0);
+        assert!(!a.is_undefined());
+        assert!(!a.is_nullish());
+    }
+
+    #[test]
+    fn test_undefined() {
+        use crate as anyrust;
+
+        let a = Any::new(_undefined);
+        assert_eq!(a.to_string(), "undefined");
+        assert_eq!(a.type_name(), "Undefined");
+        assert!(!a.to_boolean());
+        assert_ne!(a, null.clone());
+        assert_eq!(a, undefined.clone());
+
+        assert!((a.clone() + any(1)).is_nan());
+        assert!((any(1.5) * a.clone()).is_nan());
+        assert!((-a.clone()).is_nan());
+        assert_eq!(a.clone() + any("!"), any("undefined!"));
+        assert!((a.clone() + null.clone()).is_nan());
+        assert!((null.clone() * a.clone()).is_nan());
+        assert!(null.clone().pow(a.clone()).is_nan());
+        assert_eq!(a.clone() | null.clone(), any(0));
+        assert!((any(1) + null.clone()).is_null());
+        assert_eq!(a.partial_cmp(&any(0)), None);
+
+        let m = map! { "key" => null.clone() };
+        assert!(m.get("key").is_null());
+        assert!(m.get("missing").is_undefined());
+        assert!(m["missing"].is_undefined());
+        assert!(any(1).get("key").is_undefined());
+
+        assert!(any(1).to_pair().to_tuple().0.is_undefined());
+        assert!(any(1).to_function().call(array![]).is_undefined());
+
+        let f = function!(_x, y => { y });
+        assert!(f.call(array![1]).is_undefined());
+        assert!(f.call(array![1, null.clone()]).is_null());
+    }
+
     #[test]
     fn test_is_boolean() {
         let a = Any::new(true);
@@ -2090,112 +2580,384 @@ mod test_type_check_for_any {
     }
 }
 
-/// Implements basic behavior for Array objects.
+/// Implements fallible type conversion functionality.
+///
+/// Unlike the `to_*` methods, these return a [`CastError`] when there is no meaningful conversion.
 impl Any {
-    /// Adds a value to the end of the array.
-    pub fn push(&mut self, value: impl Into<Any>) {
-        if self.is_array() {
-            self.data.to_array_mut().push(value.into())
-        }
+    /// Returns the name of the stored type. (used in error messages)
+    pub(crate) fn type_name(&self) -> &'static str {
+        self.value.type_name()
     }
 
-    /// Removes the last element from the array and returns it.
-    pub fn pop(&mut self) -> Option<Any> {
-        if self.is_array() {
-            self.data.to_array_mut().pop()
-        } else {
-            None
-        }
+    fn cast_error(&self, to: &'static str) -> CastError {
+        CastError::new(self.type_name(), to)
     }
 
-    /// Adds a value to the beginning of the array.
-    pub fn unshift(&mut self, value: impl Into<Any>) {
-        if self.is_array() {
-            self.data.to_array_mut().unshift(value.into())
+    /// Converts the type to an integer.
+    ///
+    /// Succeeds for integers that fit in i64, finite floats (truncated), numeric strings and booleans.
+    pub fn try_to_integer(&self) -> Result<i64, CastError> {
+        match &self.value {
+            Value::Int(int) => i64::try_from(int.to_i128()).map_err(|_| self.cast_error("integer")),
+            Value::Bool(boolean) => Ok(*boolean as i64),
+            Value::Decimal(decimal) => decimal
+                .trunc()
+                .to_i64()
+                .ok_or_else(|| self.cast_error("integer")),
+            Value::Float(float) => {
+                float_to_integer(float.to_f64()).ok_or_else(|| self.cast_error("integer"))
+            }
+            Value::Str(string) => parse_integer(string).ok_or_else(|| self.cast_error("integer")),
+            _ => Err(self.cast_error("integer")),
         }
     }
 
-    /// Removes the first element from the array and returns it.
-    pub fn shift(&mut self) -> Option<Any> {
-        if self.is_array() {
-            self.data.to_array_mut().shift()
+    /// Converts the type to a float.
+    ///
+    /// Succeeds for numbers, numeric strings and booleans.
+    pub fn try_to_float(&self) -> Result<f64, CastError> {
+        if self.is_number() || self.is_boolean() {
+            Ok(self.to_float())
+        } else if self.is_string() {
+            parse_float(&self.to_str()).ok_or_else(|| self.cast_error("float"))
         } else {
-            None
+            Err(self.cast_error("float"))
         }
     }
 
-    /// Reverses the array.
-    pub fn reverse(&mut self) -> Any {
-        if self.is_array() {
-            self.data.to_array_mut().reverse().clone().into()
+    /// Converts the type to a boolean.
+    ///
+    /// Succeeds for booleans, numbers (zero and NaN are false) and the strings "true" and "false".
+    pub fn try_to_boolean(&self) -> Result<bool, CastError> {
+        if self.is_boolean() {
+            Ok(self.to_boolean())
+        } else if self.is_number() {
+            Ok(!self.is_nan() && self.to_boolean())
+        } else if self.is_string() {
+            self.to_str()
+                .parse::<bool>()
+                .map_err(|_| self.cast_error("boolean"))
         } else {
-            Any::from(_null)
+            Err(self.cast_error("boolean"))
         }
     }
-}
 
-/// Implements basic behavior for Map objects.
-impl Any {
-    /// Sets a key-value pair in the map.
-    pub fn set(&mut self, key: impl Into<Any>, value: impl Into<Any>) {
-        if self.is_map() {
-            self.data.to_map_mut().0.insert(key.into(), value.into());
+    /// Converts the type to an array. Only arrays can be converted.
+    pub fn try_to_array(&self) -> Result<Array, CastError> {
+        if self.is_array() {
+            Ok(self.to_array())
+        } else {
+            Err(self.cast_error("Array"))
         }
     }
 
-    /// Gets a value from the map.
-    pub fn get(&self, key: impl Into<Any>) -> Any {
+    /// Converts the type to a map. Only maps can be converted.
+    pub fn try_to_map(&self) -> Result<Map, CastError> {
         if self.is_map() {
-            self.data
-                .to_map()
-                .0
-                .get(&key.into())
-                .cloned()
-                .unwrap_or_else(|| Any::from(_null))
+            Ok(self.to_map())
         } else {
-            Any::from(_null)
+            Err(self.cast_error("Map"))
         }
     }
 
-    /// Deletes a key-value pair from the map.
-    pub fn delete(&mut self, key: impl Into<Any>) -> Any {
-        if self.is_map() {
-            self.data
-                .to_map_mut()
-                .0
-                .remove(&key.into())
-                .unwrap_or_else(|| Any::from(_null))
+    /// Converts the type to a pair. Pairs and arrays of two elements can be converted.
+    pub fn try_to_pair(&self) -> Result<Pair, CastError> {
+        if self.is_pair() || matches!(&self.value, Value::Array(array) if array.length() == 2) {
+            Ok(self.to_pair())
         } else {
-            Any::from(_null)
+            Err(self.cast_error("Pair"))
         }
     }
-}
 
-/// Implements basic behavior for Collection objects.
-impl Any {
-    /// Returns the length of the collection.
-    pub fn length(&self) -> Any {
-        if self.is_array() {
-            self.data.to_array().length().into()
-        } else if self.is_map() {
-            self.data.to_map().length().into()
-        } else if self.is_string() {
-            self.data.to_string().len().into()
+    /// Converts the type to a function. Only functions can be converted.
+    pub fn try_to_function(&self) -> Result<Function, CastError> {
+        if self.is_function() {
+            Ok(self.to_function())
         } else {
-            Any::from(_null)
+            Err(self.cast_error("Function"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_try_cast_for_any {
+    use super::*;
+
+    #[test]
+    fn test_try_to_integer() {
+        assert_eq!(Any::new(5_i8).try_to_integer(), Ok(5));
+        assert_eq!(Any::new(5_u64).try_to_integer(), Ok(5));
+        assert_eq!(Any::new(5.7).try_to_integer(), Ok(5));
+        assert_eq!(Any::new(true).try_to_integer(), Ok(1));
+        assert_eq!(Any::from(" 42 ").try_to_integer(), Ok(42));
+        assert_eq!(Any::from("4.2").try_to_integer(), Ok(4));
+
+        assert_eq!(
+            Any::from("abc").try_to_integer(),
+            Err(CastError::new("String", "integer"))
+        );
+        assert_eq!(
+            Any::new(u64::MAX).try_to_integer(),
+            Err(CastError::new("u64", "integer"))
+        );
+        assert_eq!(
+            Any::new(f64::NAN).try_to_integer(),
+            Err(CastError::new("f64", "integer"))
+        );
+        assert_eq!(
+            Any::new(_null).try_to_integer(),
+            Err(CastError::new("Null", "integer"))
+        );
+        assert_eq!(
+            Any::from(vec![1, 2]).try_to_integer(),
+            Err(CastError::new("Array", "integer"))
+        );
+    }
+
+    #[test]
+    fn test_try_to_float() {
+        assert_eq!(Any::new(5_i32).try_to_float(), Ok(5.0));
+        assert_eq!(Any::new(1.5_f32).try_to_float(), Ok(1.5));
+        assert_eq!(Any::from("1e3").try_to_float(), Ok(1000.0));
+        assert_eq!(Any::new(false).try_to_float(), Ok(0.0));
+        assert_eq!(Any::from(" -.5 ").try_to_float(), Ok(-0.5));
+        assert_eq!(Any::from("Infinity").try_to_float(), Ok(f64::INFINITY));
+        assert_eq!(Any::from("+Infinity").try_to_float(), Ok(f64::INFINITY));
+        assert_eq!(Any::from("-Infinity").try_to_float(), Ok(f64::NEG_INFINITY));
+
+        assert_eq!(
+            Any::new("abc").try_to_float(),
+            Err(CastError::new("String", "float"))
+        );
+        // accepted by Rust, but not by Number()
+        for text in [
+            "nan",
+            "NaN",
+            "inf",
+            "-inf",
+            "INF",
+            "infinity",
+            "+infinity",
+            "INFINITY",
+            "+-1",
+            "--Infinity",
+        ] {
+            assert_eq!(
+                Any::from(text).try_to_float(),
+                Err(CastError::new("String", "float")),
+                "TC: {text}"
+            );
+            assert!(Any::from(text).to_float().is_nan(), "TC: {text}");
+        }
+        assert_eq!(
+            Any::from(HashMap::new()).try_to_float(),
+            Err(CastError::new("Map", "float"))
+        );
+    }
+
+    #[test]
+    fn test_try_to_boolean() {
+        assert_eq!(Any::new(true).try_to_boolean(), Ok(true));
+        assert_eq!(Any::new(0).try_to_boolean(), Ok(false));
+        assert_eq!(Any::new(f64::NAN).try_to_boolean(), Ok(false));
+        assert_eq!(Any::from("false").try_to_boolean(), Ok(false));
+
+        assert_eq!(
+            Any::from("yes").try_to_boolean(),
+            Err(CastError::new("String", "boolean"))
+        );
+        assert_eq!(
+            Any::new(_null).try_to_boolean(),
+            Err(CastError::new("Null", "boolean"))
+        );
+    }
+
+    #[test]
+    fn test_try_to_collections() {
+        let array = Any::from(vec![1, 2]);
+        assert_eq!(array.try_to_array(), Ok(array.to_array()));
+        assert_eq!(
+            array.try_to_pair().unwrap().to_tuple(),
+            (Any::new(1), Any::new(2))
+        );
+        assert_eq!(
+            Any::new(1).try_to_array(),
+            Err(CastError::new("i32", "Array"))
+        );
+        assert_eq!(
+            Any::from(vec![1, 2, 3]).try_to_pair().unwrap_err(),
+            CastError::new("Array", "Pair")
+        );
+
+        let map = Any::from(HashMap::new());
+        assert_eq!(map.try_to_map(), Ok(Map::new()));
+        assert_eq!(array.try_to_map(), Err(CastError::new("Array", "Map")));
+
+        let function = Any::from(Function::new(|args| args, 1));
+        assert!(function.try_to_function().is_ok());
+        assert_eq!(
+            map.try_to_function().unwrap_err(),
+            CastError::new("Map", "Function")
+        );
+    }
+
+    #[test]
+    fn test_cast_error_display() {
+        let error = Any::from("abc").try_to_integer().unwrap_err();
+        assert_eq!(error.to_string(), "cannot cast String to integer");
+    }
+
+    #[test]
+    fn test_infallible_cast_fallback() {
+        assert_eq!(Any::from("abc").to_integer(), 0);
+        assert!(Any::from("abc").to_float().is_nan());
+        assert_eq!(Any::from("").to_float(), 0.0);
+        assert_eq!(Any::from("4.5").to_integer(), 4);
+        assert_eq!(Any::new(" 12 ").to_integer(), 12);
+        assert!(Any::new("abc").to_float().is_nan());
+
+        assert!((Any::from("abc") - Any::from(1)).is_nan());
+    }
+}
+
+/// Implements basic behavior for Array objects.
+///
+/// `push` and `unshift` turn null or undefined into an empty array first, so that a missing value can be filled in.
+/// Any other non-array value is left unchanged and they return a [`CastError`], so the value is never silently dropped.
+/// `pop` and `shift` return None and `reverse` does nothing for a non-array value.
+impl Any {
+    /// Adds a value to the end of the array.
+    /**
+    ```
+    use anyrust::*;
+
+    let mut a = array![1, 2];
+    a.push(3).unwrap();
+    assert_eq!(a, array![1, 2, 3]);
+
+    let mut b = null.clone();
+    b.push("first").unwrap();
+    assert_eq!(b, array!["first"]);
+
+    let mut c = any(1);
+    assert_eq!(c.push(2), Err(CastError::new("i32", "Array")));
+    assert_eq!(c, any(1));
+    ```
+    */
+    pub fn push(&mut self, value: impl Into<Any>) -> Result<(), CastError> {
+        self.promote_to_array()?.push(value.into());
+        Ok(())
+    }
+
+    /// Removes the last element from the array and returns it.
+    pub fn pop(&mut self) -> Option<Any> {
+        match &mut self.value {
+            Value::Array(array) => array.pop(),
+            _ => None,
+        }
+    }
+
+    /// Adds a value to the beginning of the array. Fails like `push` for a non-array value.
+    pub fn unshift(&mut self, value: impl Into<Any>) -> Result<(), CastError> {
+        self.promote_to_array()?.unshift(value.into());
+        Ok(())
+    }
+
+    /// Removes the first element from the array and returns it.
+    pub fn shift(&mut self) -> Option<Any> {
+        match &mut self.value {
+            Value::Array(array) => array.shift(),
+            _ => None,
+        }
+    }
+
+    /// Reverses the array in place and returns a copy of it.
+    pub fn reverse(&mut self) -> Any {
+        if let Value::Array(array) = &mut self.value {
+            array.reverse();
+        }
+        self.clone()
+    }
+
+    /// Replaces null or undefined with an empty array. Fails for any other non-array value.
+    fn promote_to_array(&mut self) -> Result<&mut Array, CastError> {
+        if self.is_nullish() {
+            self.value = Value::Array(Array::new());
+        }
+
+        let error = self.cast_error("Array");
+        match &mut self.value {
+            Value::Array(array) => Ok(array),
+            _ => Err(error),
+        }
+    }
+}
+
+/// Implements basic behavior for Map objects.
+impl Any {
+    /// Sets a key-value pair in the map.
+    pub fn set(&mut self, key: impl Into<Any>, value: impl Into<Any>) {
+        if let Value::Map(map) = &mut self.value {
+            map.set(key, value);
+        }
+    }
+
+    /// Gets a value from the map. Returns undefined if the key is missing.
+    ///
+    /// Use [`Any::get_opt`] or [`Any::path`] to look into arrays and nested values.
+    pub fn get(&self, key: impl Into<Any>) -> Any {
+        match &self.value {
+            Value::Map(map) => map
+                .get(&key.into())
+                .cloned()
+                .unwrap_or_else(|| Any::from(_undefined)),
+            _ => Any::from(_undefined),
+        }
+    }
+
+    /// Deletes a key-value pair from the map and returns the value. Returns undefined if the key is missing.
+    pub fn delete(&mut self, key: impl Into<Any>) -> Any {
+        match &mut self.value {
+            Value::Map(map) => map
+                .delete(&key.into())
+                .unwrap_or_else(|| Any::from(_undefined)),
+            Value::Set(set) => {
+                let key = key.into();
+                if set.delete(key.clone()) {
+                    key
+                } else {
+                    Any::from(_undefined)
+                }
+            }
+            _ => Any::from(_undefined),
+        }
+    }
+}
+
+/// Implements basic behavior for Collection objects.
+impl Any {
+    /// Returns the length of the collection.
+    ///
+    /// The length of a string is the number of chars.
+    /// Use [`Any::length_utf16`] for the JavaScript length, or [`Any::length_graphemes`] for the number of user-perceived characters.
+    pub fn length(&self) -> Any {
+        match &self.value {
+            Value::Array(array) => array.length().into(),
+            Value::Map(map) => map.length().into(),
+            Value::Set(set) => set.size().into(),
+            Value::Str(string) => string.chars().count().into(),
+            _ => Any::from(_null),
         }
     }
 
     /// Returns true if the collection is empty.
     pub fn is_empty(&self) -> Any {
-        if self.is_array() {
-            self.data.to_array().is_empty().into()
-        } else if self.is_map() {
-            self.data.to_map().is_empty().into()
-        } else if self.is_string() {
-            self.data.to_string().is_empty().into()
-        } else {
-            Any::from(_null)
+        match &self.value {
+            Value::Array(array) => array.is_empty().into(),
+            Value::Map(map) => map.is_empty().into(),
+            Value::Set(set) => set.is_empty().into(),
+            Value::Str(string) => string.is_empty().into(),
+            _ => Any::from(_null),
         }
     }
 }
@@ -2206,210 +2968,243 @@ impl Any {
     ///
     /// The first argument must be an Array object value.
     pub fn call(&self, args: Any) -> Any {
-        if self.is_function() {
-            self.data.to_function().call(args)
-        } else {
-            Any::from(_null)
+        match &self.value {
+            Value::Function(function) => function.call(args),
+            _ => Any::from(_null),
         }
     }
 }
 
 lazy_static::lazy_static! {
-    pub(crate) static ref I8: TypeId = TypeId::of::<i8>();
-    pub(crate) static ref I16: TypeId = TypeId::of::<i16>();
-    pub(crate) static ref I32: TypeId = TypeId::of::<i32>();
-    pub(crate) static ref I64: TypeId = TypeId::of::<i64>();
-    pub(crate) static ref ISIZE: TypeId = TypeId::of::<isize>();
-    pub(crate) static ref U8: TypeId = TypeId::of::<u8>();
-    pub(crate) static ref U16: TypeId = TypeId::of::<u16>();
-    pub(crate) static ref U32: TypeId = TypeId::of::<u32>();
-    pub(crate) static ref U64: TypeId = TypeId::of::<u64>();
-    pub(crate) static ref USIZE: TypeId = TypeId::of::<usize>();
-    pub(crate) static ref F32: TypeId = TypeId::of::<f32>();
-    pub(crate) static ref F64: TypeId = TypeId::of::<f64>();
-    pub(crate) static ref STRING: TypeId = TypeId::of::<String>();
-    pub(crate) static ref STR: TypeId = TypeId::of::<&str>();
-    pub(crate) static ref BOOL: TypeId = TypeId::of::<bool>();
-    pub(crate) static ref ARRAY: TypeId = TypeId::of::<Array>();
-    pub(crate) static ref MAP: TypeId = TypeId::of::<Map>();
-    pub(crate) static ref NULL: TypeId = TypeId::of::<Null>();
-    pub(crate) static ref FUNCTION: TypeId = TypeId::of::<Function>();
-    pub(crate) static ref PAIR: TypeId = TypeId::of::<Pair>();
-
     /// value of Null type
     pub static ref null: Any = Any::new(_null);
+    /// value of Undefined type
+    pub static ref undefined: Any = Any::new(_undefined);
     static ref EMPTY_ARRAY: Array = Array(vec![]);
-    static ref EMPTY_MAP: Map = Map(HashMap::new());
+    static ref EMPTY_MAP: Map = Map::new();
 }
 
-impl Add for Any {
-    type Output = Self;
+/// integer operation used by the arithmetic operators
+///
+/// Integers are computed in i128, which holds every operand exactly, and then stored back in the promoted type.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum IntOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl IntOp {
+    fn checked(self, a: i128, b: i128) -> Option<i128> {
+        match self {
+            IntOp::Add => a.checked_add(b),
+            IntOp::Sub => a.checked_sub(b),
+            IntOp::Mul => a.checked_mul(b),
+            IntOp::Div => a.checked_div(b),
+            IntOp::Rem => a.checked_rem(b),
+        }
+    }
 
-    fn add(self, other: Self) -> Self {
-        if self.type_id == *NULL || other.type_id == *NULL {
-            Any::new(_null)
-        } else if self.type_id == other.type_id {
-            match self.type_id {
-                type_id if type_id == *I8 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a + b)
-                }
-                type_id if type_id == *I16 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a + b)
-                }
-                type_id if type_id == *I32 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a + b)
-                }
-                type_id if type_id == *I64 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a + b)
-                }
-                type_id if type_id == *ISIZE => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a + b)
-                }
-                type_id if type_id == *U8 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a + b)
-                }
-                type_id if type_id == *U16 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a + b)
-                }
-                type_id if type_id == *U32 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a + b)
-                }
-                type_id if type_id == *U64 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a + b)
-                }
-                type_id if type_id == *USIZE => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a + b)
-                }
-                type_id if type_id == *F32 => {
-                    let a = self.data.to_float();
-                    let b = other.data.to_float();
-                    Any::new(a + b)
-                }
-                type_id if type_id == *F64 => {
-                    let a = self.data.to_float();
-                    let b = other.data.to_float();
-                    Any::new(a + b)
-                }
-                type_id if type_id == *STRING => {
-                    let mut a = self.data.to_string();
-                    let b = other.data.to_string();
-                    a.push_str(b.as_str());
-                    Any::new(a)
-                }
-                type_id if type_id == *STR => {
-                    let mut a = self.data.to_string();
-                    let b = other.data.to_string();
-                    a.push_str(b.as_str());
-                    Any::new(a)
-                }
-                type_id if type_id == *BOOL => {
-                    let a = self.data.to_boolean();
-                    let b = other.data.to_boolean();
-                    Any::new(a || b)
-                }
-                // TODO: ARRAY, MAP
-                type_id if type_id == *ARRAY => {
-                    let a = self.data.to_array();
-                    let b = other.data.to_array();
-                    let mut result = a.clone();
-                    result.0.extend(b.0.clone());
-                    Any::new(result)
-                }
-                _ => {
-                    let a = self.data.to_string();
-                    let b = other.data.to_string();
-                    Any::new(a + &b)
-                }
+    /// Returns None only for a zero divisor.
+    fn wrapping(self, a: i128, b: i128) -> Option<i128> {
+        match self {
+            IntOp::Add => Some(a.wrapping_add(b)),
+            IntOp::Sub => Some(a.wrapping_sub(b)),
+            IntOp::Mul => Some(a.wrapping_mul(b)),
+            IntOp::Div => a.checked_div(b),
+            IntOp::Rem => a.checked_rem(b),
+        }
+    }
+
+    /// Returns None only for a zero divisor.
+    fn saturating(self, a: i128, b: i128) -> Option<i128> {
+        match self {
+            IntOp::Add => Some(a.saturating_add(b)),
+            IntOp::Sub => Some(a.saturating_sub(b)),
+            IntOp::Mul => Some(a.saturating_mul(b)),
+            IntOp::Div => a.checked_div(b),
+            IntOp::Rem => a.checked_rem(b),
+        }
+    }
+
+    fn float(self, a: f64, b: f64) -> f64 {
+        match self {
+            IntOp::Add => a + b,
+            IntOp::Sub => a - b,
+            IntOp::Mul => a * b,
+            IntOp::Div => a / b,
+            IntOp::Rem => a % b,
+        }
+    }
+}
+
+/// How integer overflow is handled.
+#[derive(Debug, Clone, Copy)]
+enum Overflow {
+    /// The result is computed again in f64, like JavaScript numbers. This is what the operators do.
+    Float,
+    Wrap,
+    Saturate,
+}
+
+/// numeric operands after the promotion rules
+enum Operands {
+    /// result type and the exact values
+    Int(Int, i128, i128),
+    Decimal(Decimal, Decimal),
+    Float(f64, f64),
+}
+
+/// Converts an operand to an integer. A boolean takes the type of the other operand, like a literal 0 or 1.
+fn as_int(value: &Any, other: Int) -> Int {
+    match &value.value {
+        Value::Int(int) => *int,
+        Value::Bool(boolean) => other.wrapping_with(*boolean as i128),
+        _ => Int::I64(value.to_integer()),
+    }
+}
+
+/// Applies the promotion rules shared by the arithmetic operators.
+///
+/// A float on either side promotes both operands to f64. Otherwise a Decimal on either side gives a Decimal,
+/// unless the other operand does not fit in one. Otherwise an integer on either side gives an integer
+/// of the type chosen by the promotion lattice (see `Int::promote`).
+/// Returns None if neither operand is a number.
+fn numeric_operands(a: &Any, b: &Any) -> Option<Operands> {
+    match (&a.value, &b.value) {
+        // undefined converts to NaN
+        (Value::Float(_) | Value::Undefined, _) | (_, Value::Float(_) | Value::Undefined) => {
+            Some(Operands::Float(a.to_float(), b.to_float()))
+        }
+        (Value::Decimal(_), _) | (_, Value::Decimal(_)) => {
+            match (decimal::as_decimal(a), decimal::as_decimal(b)) {
+                (Some(a), Some(b)) => Some(Operands::Decimal(a, b)),
+                _ => Some(Operands::Float(a.to_float(), b.to_float())),
             }
-        } else if self.type_id == *STRING || other.type_id == *STRING {
-            let a = self.data.to_string();
-            let b = other.data.to_string();
-            Any::new(a + &b)
-        } else if self.type_id == *STR || other.type_id == *STR {
-            let a = self.data.to_string();
-            let b = other.data.to_string();
-            Any::new(a + &b)
-        } else if self.type_id == *F64 || other.type_id == *F64 {
-            let a: f64 = self.data.to_float();
-            let b = other.data.to_float();
-            Any::new(a + b)
-        } else if self.type_id == *F32 || other.type_id == *F32 {
-            let a: f64 = self.data.to_float();
-            let b = other.data.to_float();
-            Any::new(a + b)
-        } else if self.type_id == *I64 || other.type_id == *I64 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a + b)
-        } else if self.type_id == *I32 || other.type_id == *I32 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a + b)
-        } else if self.type_id == *I16 || other.type_id == *I16 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a + b)
-        } else if self.type_id == *I8 || other.type_id == *I8 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            let result = a + b;
-            Any::new(result)
-        } else if self.type_id == *U64 || other.type_id == *U64 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a + b)
-        } else if self.type_id == *U32 || other.type_id == *U32 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a + b)
-        } else if self.type_id == *U16 || other.type_id == *U16 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a + b)
-        } else if self.type_id == *U8 || other.type_id == *U8 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a + b)
-        } else {
-            let a = self.data.to_string();
-            let b = other.data.to_string();
-            Any::new(a + &b)
         }
+        (Value::Int(int), _) | (_, Value::Int(int)) => {
+            let (a, b) = (as_int(a, *int), as_int(b, *int));
+            Some(Operands::Int(Int::promote(a, b), a.to_i128(), b.to_i128()))
+        }
+        _ => None,
     }
 }
 
-impl AddAssign for Any {
-    fn add_assign(&mut self, other: Self) {
-        *self = self.clone() + other;
+/// Applies a numeric operator. Returns None if neither operand is a number.
+///
+/// A zero divisor falls back to f64, so division by zero gives Infinity or NaN like JavaScript.
+/// Integers and Decimals also fall back to f64 on overflow.
+fn arithmetic(a: &Any, b: &Any, op: IntOp, overflow: Overflow) -> Option<Any> {
+    let result = match numeric_operands(a, b)? {
+        Operands::Float(a, b) => Any::new(op.float(a, b)),
+        Operands::Decimal(a, b) => decimal::decimal_arithmetic(a, b, op),
+        Operands::Int(int, a, b) => {
+            let result = match overflow {
+                Overflow::Float => op.checked(a, b).and_then(|value| int.checked_with(value)),
+                Overflow::Wrap => op.wrapping(a, b).map(|value| int.wrapping_with(value)),
+                Overflow::Saturate => op.saturating(a, b).map(|value| int.saturating_with(value)),
+            };
+
+            match result {
+                Some(int) => Any {
+                    value: Value::Int(int),
+                },
+                None => Any::new(op.float(a as f64, b as f64)),
+            }
+        }
+    };
+
+    Some(result)
+}
+
+/// Returns the promoted type and the values if both operands are integers. (a boolean counts as an integer here)
+fn integer_operands(a: &Any, b: &Any) -> Option<(Int, i128, i128)> {
+    match (&a.value, &b.value) {
+        (Value::Int(_) | Value::Bool(_), Value::Int(_) | Value::Bool(_)) => {
+            match numeric_operands(a, b)? {
+                Operands::Int(int, a, b) => Some((int, a, b)),
+                Operands::Decimal(..) | Operands::Float(..) => None,
+            }
+        }
+        _ => None,
     }
 }
 
+macro_rules! impl_overflow_methods {
+    ($($op:ident, $trait:ident, $method:ident, $checked:ident, $wrapping:ident, $saturating:ident);*) => {
+        /// Implements the integer overflow modes of the arithmetic operators.
+        ///
+        /// If both operands are integers, the operation is done in the promoted type and overflow is handled
+        /// as the method name says, instead of falling back to f64.
+        /// Any other operands give the same result as the operator.
+        impl Any {
+            $(
+                #[doc = concat!("Checked `", stringify!($method), "`. Returns None on overflow or division by zero, or if only one operand is a BigInt.")]
+                ///
+                /// Unlike the operator, a Decimal never falls back to f64.
+                pub fn $checked(&self, other: impl Into<Any>) -> Option<Any> {
+                    let other = other.into();
+
+                    if self.is_bigint() || other.is_bigint() {
+                        return bigint::checked_bigint_arithmetic(self, &other, IntOp::$op);
+                    }
+                    if let Some((a, b)) = decimal::decimal_operands(self, &other) {
+                        return IntOp::$op.decimal(a, b).map(Any::new);
+                    }
+
+                    match integer_operands(self, &other) {
+                        Some((int, a, b)) => IntOp::$op
+                            .checked(a, b)
+                            .and_then(|value| int.checked_with(value))
+                            .map(|int| Any {
+                                value: Value::Int(int),
+                            }),
+                        None => Some($trait::$method(self.clone(), other)),
+                    }
+                }
+
+                #[doc = concat!("Wrapping `", stringify!($method), "`. Division by zero gives the same result as the operator.")]
+                pub fn $wrapping(&self, other: impl Into<Any>) -> Any {
+                    let other = other.into();
+
+                    match integer_operands(self, &other) {
+                        Some(_) => arithmetic(self, &other, IntOp::$op, Overflow::Wrap)
+                            .unwrap_or_else(|| unreachable!()),
+                        None => $trait::$method(self.clone(), other),
+                    }
+                }
+
+                #[doc = concat!("Saturating `", stringify!($method), "`. Division by zero gives the same result as the operator.")]
+                pub fn $saturating(&self, other: impl Into<Any>) -> Any {
+                    let other = other.into();
+
+                    match integer_operands(self, &other) {
+                        Some(_) => arithmetic(self, &other, IntOp::$op, Overflow::Saturate)
+                            .unwrap_or_else(|| unreachable!()),
+                        None => $trait::$method(self.clone(), other),
+                    }
+                }
+            )*
+        }
+    };
+}
+
+impl_overflow_methods!(
+    Add, Add, add, checked_add, wrapping_add, saturating_add;
+    Sub, Sub, sub, checked_sub, wrapping_sub, saturating_sub;
+    Mul, Mul, mul, checked_mul, wrapping_mul, saturating_mul;
+    Div, Div, div, checked_div, wrapping_div, saturating_div;
+    Rem, Rem, rem, checked_rem, wrapping_rem, saturating_rem
+);
+
 #[cfg(test)]
-mod test_add_for_any {
+mod test_integer_promotion {
     use super::*;
 
     #[test]
-    fn test_add() {
+    fn test_promotion() {
         struct TestCase {
             name: String,
             a: Any,
@@ -2419,175 +3214,303 @@ mod test_add_for_any {
 
         let test_cases = vec![
             TestCase {
-                name: "i64".to_string(),
-                a: Any::new(5_i64),
-                b: Any::new(10_i64),
-                result: Any::new(15_i64),
+                name: "u8 + u8".to_string(),
+                a: Any::new(5_u8),
+                b: Any::new(5_u8),
+                result: Any::new(10_u8),
             },
             TestCase {
-                name: "u64".to_string(),
+                name: "i16 + i64".to_string(),
+                a: Any::new(5_i16),
+                b: Any::new(5_i64),
+                result: Any::new(10_i64),
+            },
+            TestCase {
+                name: "u16 + u32".to_string(),
+                a: Any::new(5_u16),
+                b: Any::new(5_u32),
+                result: Any::new(10_u32),
+            },
+            TestCase {
+                name: "i32 + u8".to_string(),
+                a: Any::new(5_i32),
+                b: Any::new(5_u8),
+                result: Any::new(10_i32),
+            },
+            TestCase {
+                name: "i8 + u8".to_string(),
+                a: Any::new(5_i8),
+                b: Any::new(5_u8),
+                result: Any::new(10_i16),
+            },
+            TestCase {
+                name: "u32 + i32".to_string(),
+                a: Any::new(5_u32),
+                b: Any::new(5_i32),
+                result: Any::new(10_i64),
+            },
+            TestCase {
+                name: "u64 + i8".to_string(),
                 a: Any::new(5_u64),
-                b: Any::new(10_u64),
-                result: Any::new(15_i64),
+                b: Any::new(5_i8),
+                result: Any::new(10_i64),
             },
             TestCase {
-                name: "f32".to_string(),
-                a: Any::new(5.0_f32),
-                b: Any::new(10.0_f32),
-                result: Any::new(15.0_f64),
+                name: "isize + isize".to_string(),
+                a: Any::new(5_isize),
+                b: Any::new(5_isize),
+                result: Any::new(10_isize),
             },
             TestCase {
-                name: "f64".to_string(),
-                a: Any::new(5.0),
-                b: Any::new(10.0),
-                result: Any::new(15.0),
+                name: "isize + i64".to_string(),
+                a: Any::new(5_isize),
+                b: Any::new(5_i64),
+                result: Any::new(10_i64),
             },
             TestCase {
-                name: "string".to_string(),
-                a: Any::new("5".to_string()),
-                b: Any::new("10".to_string()),
-                result: Any::new("510".to_string()),
+                name: "isize + u32".to_string(),
+                a: Any::new(5_isize),
+                b: Any::new(5_u32),
+                result: Any::new(10_isize),
             },
             TestCase {
-                name: "str".to_string(),
-                a: Any::new("5"),
-                b: Any::new("10"),
-                result: Any::new("510".to_string()),
+                name: "usize + u64".to_string(),
+                a: Any::new(5_usize),
+                b: Any::new(5_u64),
+                result: Any::new(10_u64),
+            },
+            TestCase {
+                name: "u8 + bool".to_string(),
+                a: Any::new(5_u8),
+                b: Any::new(true),
+                result: Any::new(6_u8),
+            },
+            TestCase {
+                name: "u8 overflow".to_string(),
+                a: Any::new(200_u8),
+                b: Any::new(100_u8),
+                result: Any::new(300.0),
+            },
+            TestCase {
+                name: "u64 above i64::MAX".to_string(),
+                a: Any::new(u64::MAX - 1),
+                b: Any::new(1_u64),
+                result: Any::new(u64::MAX),
+            },
+            TestCase {
+                name: "i64 overflow".to_string(),
+                a: Any::new(i64::MAX),
+                b: Any::new(1_i64),
+                result: Any::new(i64::MAX as f64 + 1.0),
             },
         ];
 
         for test_case in test_cases {
             let result = test_case.a + test_case.b;
             assert_eq!(result, test_case.result, "TC: {}", test_case.name);
+            assert_eq!(
+                result.value.type_name(),
+                test_case.result.value.type_name(),
+                "TC: {}",
+                test_case.name
+            );
         }
     }
 
     #[test]
-    fn test_add_assign() {
-        let mut a = Any::new(5_i64);
-        let b = Any::new(10_i64);
-        a += b;
-        assert_eq!(a, Any::new(15_i64));
+    fn test_overflow_to_float() {
+        assert_eq!(Any::new(3_u8) - Any::new(5_u8), Any::new(-2.0));
+        assert_eq!(Any::new(16_u8) * Any::new(16_u8), Any::new(256.0));
+        assert_eq!(
+            Any::new(i64::MIN) / Any::new(-1_i64),
+            Any::new(-(i64::MIN as f64))
+        );
+        assert_eq!(Any::new(5) / Any::new(0), Any::new(f64::INFINITY));
+        assert_eq!(Any::new(-5) / Any::new(0), Any::new(f64::NEG_INFINITY));
+        assert!((Any::new(0) / Any::new(0)).is_nan());
     }
-}
 
-impl Sub for Any {
-    type Output = Self;
+    #[test]
+    fn test_to_integer_saturates() {
+        assert_eq!(Any::new(u64::MAX).to_integer(), i64::MAX);
+        assert_eq!(Any::new(usize::MAX).to_integer(), i64::MAX);
+        assert_ne!(Any::new(u64::MAX), Any::new(i64::MAX as u64));
+    }
 
-    fn sub(self, other: Self) -> Self {
-        if self.type_id == *NULL || other.type_id == *NULL {
-            Any::new(_null)
-        } else if self.type_id == other.type_id {
-            match self.type_id {
-                type_id if type_id == *I8 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a - b)
-                }
-                type_id if type_id == *I16 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a - b)
-                }
-                type_id if type_id == *I32 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a - b)
-                }
-                type_id if type_id == *I64 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a - b)
-                }
-                type_id if type_id == *ISIZE => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a - b)
-                }
-                type_id if type_id == *U8 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a - b)
-                }
-                type_id if type_id == *U16 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a - b)
-                }
-                type_id if type_id == *U32 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a - b)
-                }
-                type_id if type_id == *U64 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a - b)
-                }
-                type_id if type_id == *USIZE => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a - b)
-                }
-                type_id if type_id == *F32 => {
-                    let a = self.data.to_float();
-                    let b = other.data.to_float();
-                    Any::new(a - b)
-                }
-                type_id if type_id == *F64 => {
-                    let a = self.data.to_float();
-                    let b = other.data.to_float();
-                    Any::new(a - b)
-                }
-                _ => Any::new(f64::NAN),
+    #[test]
+    fn test_checked() {
+        assert_eq!(Any::new(200_u8).checked_add(55_u8), Some(Any::new(255_u8)));
+        assert_eq!(Any::new(200_u8).checked_add(56_u8), None);
+        assert_eq!(Any::new(3_u8).checked_sub(5_u8), None);
+        assert_eq!(Any::new(i64::MIN).checked_div(-1_i64), None);
+        assert_eq!(Any::new(5).checked_div(0), None);
+        assert_eq!(Any::new(5).checked_rem(0), None);
+        assert_eq!(Any::new(7).checked_rem(4), Some(Any::new(3)));
+        assert_eq!(Any::new(1.5).checked_mul(2), Some(Any::new(3.0)));
+        assert_eq!(Any::new("a").checked_add(1), Some(Any::new("a1")));
+    }
+
+    #[test]
+    fn test_wrapping() {
+        assert_eq!(Any::new(200_u8).wrapping_add(100_u8), Any::new(44_u8));
+        assert_eq!(Any::new(3_u8).wrapping_sub(5_u8), Any::new(254_u8));
+        assert_eq!(Any::new(i32::MAX).wrapping_mul(2), Any::new(-2));
+        assert_eq!(Any::new(u64::MAX).wrapping_mul(u64::MAX), Any::new(1_u64));
+        assert_eq!(Any::new(i64::MIN).wrapping_div(-1_i64), Any::new(i64::MIN));
+        assert_eq!(Any::new(5).wrapping_div(0), Any::new(f64::INFINITY));
+        assert_eq!(Any::new(1.5).wrapping_add(1), Any::new(2.5));
+    }
+
+    #[test]
+    fn test_saturating() {
+        assert_eq!(Any::new(200_u8).saturating_add(100_u8), Any::new(255_u8));
+        assert_eq!(Any::new(3_u8).saturating_sub(5_u8), Any::new(0_u8));
+        assert_eq!(Any::new(i32::MIN).saturating_mul(2), Any::new(i32::MIN));
+        assert_eq!(
+            Any::new(u64::MAX).saturating_mul(u64::MAX),
+            Any::new(u64::MAX)
+        );
+        assert_eq!(
+            Any::new(i64::MIN).saturating_div(-1_i64),
+            Any::new(i64::MAX)
+        );
+        assert_eq!(Any::new(-100_i8).saturating_sub(100_i8), Any::new(i8::MIN));
+    }
+
+    #[test]
+    fn test_neg() {
+        assert_eq!(-Any::new(5_u8), Any::new(-5_i16));
+        assert_eq!(-Any::new(5_u32), Any::new(-5_i64));
+        assert_eq!(-Any::new(5_u64), Any::new(-5_i64));
+        assert_eq!(-Any::new(u64::MAX), Any::new(-(u64::MAX as f64)));
+        assert_eq!(-Any::new(5_i8), Any::new(-5_i8));
+        assert_eq!(-Any::new(i8::MIN), Any::new(128.0));
+        assert_eq!(-Any::new(5_isize), Any::new(-5_isize));
+    }
+}
+
+/// Strings concatenate, and numbers add like JavaScript.
+///
+/// A Decimal stays exact with an integer or another Decimal, while a float on either side gives an f64.
+/// A Decimal result that overflows becomes an f64; use `checked_add` to get None instead.
+impl Add for Any {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        match (self.value, other.value) {
+            (Value::Null, Value::Undefined) | (Value::Undefined, Value::Null) => Any::new(f64::NAN),
+            (Value::Null, _) | (_, Value::Null) => Any::new(_null),
+            (Value::Str(mut a), b) => {
+                a.push_str(&b.as_anyable().to_string());
+                Any::new(a)
             }
-        } else if self.type_id == *STRING || other.type_id == *STRING {
-            Any::new(f64::NAN)
-        } else if self.type_id == *STR || other.type_id == *STR {
-            Any::new(f64::NAN)
-        } else if self.type_id == *F64 || other.type_id == *F64 {
-            let a: f64 = self.data.to_float();
-            let b = other.data.to_float();
-            Any::new(a - b)
-        } else if self.type_id == *F32 || other.type_id == *F32 {
-            let a: f64 = self.data.to_float();
-            let b = other.data.to_float();
-            Any::new(a - b)
-        } else if self.type_id == *I64 || other.type_id == *I64 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a - b)
-        } else if self.type_id == *I32 || other.type_id == *I32 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a - b)
-        } else if self.type_id == *I16 || other.type_id == *I16 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a - b)
-        } else if self.type_id == *I8 || other.type_id == *I8 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            let result = a + b;
-            Any::new(result)
-        } else if self.type_id == *U64 || other.type_id == *U64 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a - b)
-        } else if self.type_id == *U32 || other.type_id == *U32 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a - b)
-        } else if self.type_id == *U16 || other.type_id == *U16 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a - b)
-        } else if self.type_id == *U8 || other.type_id == *U8 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a - b)
-        } else {
-            Any::new(f64::NAN)
+            (a, Value::Str(b)) => Any::new(a.as_anyable().to_string() + &b),
+            (Value::Bool(a), Value::Bool(b)) => Any::new(a || b),
+            (Value::Array(mut a), Value::Array(b)) => {
+                a.0.extend(b.0);
+                Any::new(a)
+            }
+            (a @ Value::BigInt(_), b) | (a, b @ Value::BigInt(_)) => {
+                bigint::bigint_arithmetic(&Any { value: a }, &Any { value: b }, IntOp::Add)
+            }
+            (a, b) => {
+                let (a, b) = (Any { value: a }, Any { value: b });
+                arithmetic(&a, &b, IntOp::Add, Overflow::Float)
+                    .unwrap_or_else(|| Any::new(a.to_string() + &b.to_string()))
+            }
+        }
+    }
+}
+
+impl AddAssign for Any {
+    fn add_assign(&mut self, other: Self) {
+        *self = self.clone() + other;
+    }
+}
+
+#[cfg(test)]
+mod test_add_for_any {
+    use super::*;
+
+    #[test]
+    fn test_add() {
+        struct TestCase {
+            name: String,
+            a: Any,
+            b: Any,
+            result: Any,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                name: "i64".to_string(),
+                a: Any::new(5_i64),
+                b: Any::new(10_i64),
+                result: Any::new(15_i64),
+            },
+            TestCase {
+                name: "u64".to_string(),
+                a: Any::new(5_u64),
+                b: Any::new(10_u64),
+                result: Any::new(15_u64),
+            },
+            TestCase {
+                name: "f32".to_string(),
+                a: Any::new(5.0_f32),
+                b: Any::new(10.0_f32),
+                result: Any::new(15.0_f64),
+            },
+            TestCase {
+                name: "f64".to_string(),
+                a: Any::new(5.0),
+                b: Any::new(10.0),
+                result: Any::new(15.0),
+            },
+            TestCase {
+                name: "string".to_string(),
+                a: Any::new("5".to_string()),
+                b: Any::new("10".to_string()),
+                result: Any::new("510".to_string()),
+            },
+            TestCase {
+                name: "str".to_string(),
+                a: Any::new("5"),
+                b: Any::new("10"),
+                result: Any::new("510".to_string()),
+            },
+        ];
+
+        for test_case in test_cases {
+            let result = test_case.a + test_case.b;
+            assert_eq!(result, test_case.result, "TC: {}", test_case.name);
+        }
+    }
+
+    #[test]
+    fn test_add_assign() {
+        let mut a = Any::new(5_i64);
+        let b = Any::new(10_i64);
+        a += b;
+        assert_eq!(a, Any::new(15_i64));
+    }
+}
+
+/// Panics if only one operand is a BigInt.
+///
+/// A Decimal with a float, or a Decimal result that overflows, gives an f64. Use `checked_sub` to get None on overflow.
+impl Sub for Any {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        match (&self.value, &other.value) {
+            (Value::Null, Value::Undefined) | (Value::Undefined, Value::Null) => Any::new(f64::NAN),
+            (Value::Null, _) | (_, Value::Null) => Any::new(_null),
+            (Value::BigInt(_), _) | (_, Value::BigInt(_)) => {
+                bigint::bigint_arithmetic(&self, &other, IntOp::Sub)
+            }
+            (Value::Str(_), _) | (_, Value::Str(_)) => Any::new(f64::NAN),
+            _ => arithmetic(&self, &other, IntOp::Sub, Overflow::Float)
+                .unwrap_or_else(|| Any::new(f64::NAN)),
         }
     }
 }
@@ -2619,10 +3542,10 @@ mod test_sub_for_any {
                 result: Any::new(-5_i64),
             },
             TestCase {
-                name: "u64".to_string(),
+                name: "u64 below zero".to_string(),
                 a: Any::new(5_u64),
                 b: Any::new(10_u64),
-                result: Any::new(-5_i64),
+                result: Any::new(-5.0),
             },
             TestCase {
                 name: "f32".to_string(),
@@ -2657,44 +3580,18 @@ impl Neg for Any {
     type Output = Self;
 
     fn neg(self) -> Self {
-        if self.type_id == *I8 {
-            let a = self.data.to_integer();
-            Any::new(-a)
-        } else if self.type_id == *I16 {
-            let a = self.data.to_integer();
-            Any::new(-a)
-        } else if self.type_id == *I32 {
-            let a = self.data.to_integer();
-            Any::new(-a)
-        } else if self.type_id == *I64 {
-            let a = self.data.to_integer();
-            Any::new(-a)
-        } else if self.type_id == *ISIZE {
-            let a = self.data.to_integer();
-            Any::new(-a)
-        } else if self.type_id == *U8 {
-            let a = self.data.to_integer();
-            Any::new(-a)
-        } else if self.type_id == *U16 {
-            let a = self.data.to_integer();
-            Any::new(-a)
-        } else if self.type_id == *U32 {
-            let a = self.data.to_integer();
-            Any::new(-a)
-        } else if self.type_id == *U64 {
-            let a = self.data.to_integer();
-            Any::new(-a)
-        } else if self.type_id == *USIZE {
-            let a = self.data.to_integer();
-            Any::new(-a)
-        } else if self.type_id == *F32 {
-            let a = self.data.to_float();
-            Any::new(-a)
-        } else if self.type_id == *F64 {
-            let a = self.data.to_float();
-            Any::new(-a)
-        } else {
-            Any::new(f64::NAN)
+        match self.value {
+            // unsigned types become the next signed type, like an operation with an i8
+            Value::Int(int) => match Int::promote(int, Int::I8(0)).checked_with(-int.to_i128()) {
+                Some(int) => Any {
+                    value: Value::Int(int),
+                },
+                None => Any::new(-int.to_f64()),
+            },
+            Value::Float(float) => Any::new(-float.to_f64()),
+            Value::BigInt(bigint) => Any::new(-*bigint),
+            Value::Decimal(decimal) => Any::new(-decimal),
+            _ => Any::new(f64::NAN),
         }
     }
 }
@@ -2741,123 +3638,22 @@ mod test_neg_for_any {
     }
 }
 
+/// Panics if only one operand is a BigInt.
+///
+/// A Decimal with a float, or a Decimal result that overflows, gives an f64. Use `checked_mul` to get None on overflow.
 impl Mul for Any {
     type Output = Self;
 
     fn mul(self, other: Self) -> Self {
-        if self.type_id == *NULL || other.type_id == *NULL {
-            Any::new(_null)
-        } else if self.type_id == other.type_id {
-            match self.type_id {
-                type_id if type_id == *I8 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a * b)
-                }
-                type_id if type_id == *I16 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a * b)
-                }
-                type_id if type_id == *I32 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a * b)
-                }
-                type_id if type_id == *I64 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a * b)
-                }
-                type_id if type_id == *ISIZE => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a * b)
-                }
-                type_id if type_id == *U8 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a * b)
-                }
-                type_id if type_id == *U16 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a * b)
-                }
-                type_id if type_id == *U32 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a * b)
-                }
-                type_id if type_id == *U64 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a * b)
-                }
-                type_id if type_id == *USIZE => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a * b)
-                }
-                type_id if type_id == *F32 => {
-                    let a = self.data.to_float();
-                    let b = other.data.to_float();
-                    Any::new(a * b)
-                }
-                type_id if type_id == *F64 => {
-                    let a = self.data.to_float();
-                    let b = other.data.to_float();
-                    Any::new(a * b)
-                }
-                _ => Any::new(f64::NAN),
+        match (&self.value, &other.value) {
+            (Value::Null, Value::Undefined) | (Value::Undefined, Value::Null) => Any::new(f64::NAN),
+            (Value::Null, _) | (_, Value::Null) => Any::new(_null),
+            (Value::BigInt(_), _) | (_, Value::BigInt(_)) => {
+                bigint::bigint_arithmetic(&self, &other, IntOp::Mul)
             }
-        } else if self.type_id == *STRING || other.type_id == *STRING {
-            Any::new(f64::NAN)
-        } else if self.type_id == *STR || other.type_id == *STR {
-            Any::new(f64::NAN)
-        } else if self.type_id == *F64 || other.type_id == *F64 {
-            let a: f64 = self.data.to_float();
-            let b = other.data.to_float();
-            Any::new(a * b)
-        } else if self.type_id == *F32 || other.type_id == *F32 {
-            let a: f64 = self.data.to_float();
-            let b = other.data.to_float();
-            Any::new(a * b)
-        } else if self.type_id == *I64 || other.type_id == *I64 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a * b)
-        } else if self.type_id == *I32 || other.type_id == *I32 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a * b)
-        } else if self.type_id == *I16 || other.type_id == *I16 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a * b)
-        } else if self.type_id == *I8 || other.type_id == *I8 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            let result = a + b;
-            Any::new(result)
-        } else if self.type_id == *U64 || other.type_id == *U64 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a * b)
-        } else if self.type_id == *U32 || other.type_id == *U32 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a * b)
-        } else if self.type_id == *U16 || other.type_id == *U16 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a * b)
-        } else if self.type_id == *U8 || other.type_id == *U8 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a * b)
-        } else {
-            Any::new(f64::NAN)
+            (Value::Str(_), _) | (_, Value::Str(_)) => Any::new(f64::NAN),
+            _ => arithmetic(&self, &other, IntOp::Mul, Overflow::Float)
+                .unwrap_or_else(|| Any::new(f64::NAN)),
         }
     }
 }
@@ -2892,7 +3688,7 @@ mod test_mul_for_any {
                 name: "u64".to_string(),
                 a: Any::new(5_u64),
                 b: Any::new(10_u64),
-                result: Any::new(50_i64),
+                result: Any::new(50_u64),
             },
             TestCase {
                 name: "f32".to_string(),
@@ -2923,349 +3719,1549 @@ mod test_mul_for_any {
     }
 }
 
+/// Division by zero gives Infinity or NaN like JavaScript, also for a Decimal, but a BigInt divided by zero panics.
+/// Use `checked_div` to get None instead.
 impl Div for Any {
     type Output = Self;
 
     fn div(self, other: Self) -> Self {
-        if self.type_id == *NULL || other.type_id == *NULL {
-            Any::new(_null)
-        } else if self.type_id == other.type_id {
-            match self.type_id {
-                type_id if type_id == *I8 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a / b)
-                }
-                type_id if type_id == *I16 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a / b)
-                }
-                type_id if type_id == *I32 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a / b)
-                }
-                type_id if type_id == *I64 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a / b)
-                }
-                type_id if type_id == *ISIZE => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a / b)
-                }
-                type_id if type_id == *U8 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a / b)
-                }
-                type_id if type_id == *U16 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a / b)
-                }
-                type_id if type_id == *U32 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a / b)
-                }
-                type_id if type_id == *U64 => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a / b)
-                }
-                type_id if type_id == *USIZE => {
-                    let a = self.data.to_integer();
-                    let b = other.data.to_integer();
-                    Any::new(a / b)
-                }
-                type_id if type_id == *F32 => {
-                    let a = self.data.to_float();
-                    let b = other.data.to_float();
-                    Any::new(a / b)
-                }
-                type_id if type_id == *F64 => {
-                    let a = self.data.to_float();
-                    let b = other.data.to_float();
-                    Any::new(a / b)
-                }
-                _ => Any::new(f64::NAN),
+        match (&self.value, &other.value) {
+            (Value::Null, Value::Undefined) | (Value::Undefined, Value::Null) => Any::new(f64::NAN),
+            (Value::Null, _) | (_, Value::Null) => Any::new(_null),
+            (Value::BigInt(_), _) | (_, Value::BigInt(_)) => {
+                bigint::bigint_arithmetic(&self, &other, IntOp::Div)
+            }
+            (Value::Str(_), _) | (_, Value::Str(_)) => Any::new(f64::NAN),
+            _ => arithmetic(&self, &other, IntOp::Div, Overflow::Float)
+                .unwrap_or_else(|| Any::new(f64::NAN)),
+        }
+    }
+}
+
+impl DivAssign for Any {
+    fn div_assign(&mut self, other: Self) {
+        *self = self.clone() / other;
+    }
+}
+
+#[cfg(test)]
+mod test_div_for_any {
+    use super::*;
+
+    #[test]
+    fn test_div() {
+        struct TestCase {
+            name: String,
+            a: Any,
+            b: Any,
+            result: Any,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                name: "i64".to_string(),
+                a: Any::new(5_i64),
+                b: Any::new(10_i64),
+                result: Any::new(0_i64),
+            },
+            TestCase {
+                name: "u64".to_string(),
+                a: Any::new(5_u64),
+                b: Any::new(10_u64),
+                result: Any::new(0_u64),
+            },
+            TestCase {
+                name: "f32".to_string(),
+                a: Any::new(5.0_f32),
+                b: Any::new(10.0_f32),
+                result: Any::new(0.5_f64),
+            },
+            TestCase {
+                name: "f64".to_string(),
+                a: Any::new(5.0),
+                b: Any::new(10.0),
+                result: Any::new(0.5),
+            },
+        ];
+
+        for test_case in test_cases {
+            let result = test_case.a / test_case.b;
+            assert_eq!(result, test_case.result, "TC: {}", test_case.name);
+        }
+    }
+
+    #[test]
+    fn test_div_assign() {
+        let mut a = Any::new(5_i64);
+        let b = Any::new(10_i64);
+        a /= b;
+        assert_eq!(a, Any::new(0_i64));
+    }
+}
+
+/// Panics for a BigInt remainder by zero. Use `checked_rem` to get None instead.
+impl Rem for Any {
+    type Output = Self;
+
+    /// Follows JavaScript `%`: the result has the sign of the dividend, and a zero divisor gives NaN.
+    fn rem(self, other: Self) -> Self {
+        match (&self.value, &other.value) {
+            (Value::Null, Value::Undefined) | (Value::Undefined, Value::Null) => Any::new(f64::NAN),
+            (Value::Null, _) | (_, Value::Null) => Any::new(_null),
+            (Value::BigInt(_), _) | (_, Value::BigInt(_)) => {
+                bigint::bigint_arithmetic(&self, &other, IntOp::Rem)
+            }
+            (Value::Str(_), _) | (_, Value::Str(_)) => Any::new(f64::NAN),
+            _ => arithmetic(&self, &other, IntOp::Rem, Overflow::Float)
+                .unwrap_or_else(|| Any::new(f64::NAN)),
+        }
+    }
+}
+
+impl RemAssign for Any {
+    fn rem_assign(&mut self, other: Self) {
+        *self = self.clone() % other;
+    }
+}
+
+#[cfg(test)]
+mod test_rem_for_any {
+    use super::*;
+
+    #[test]
+    fn test_rem() {
+        struct TestCase {
+            name: String,
+            a: Any,
+            b: Any,
+            result: Any,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                name: "i64".to_string(),
+                a: Any::new(7_i64),
+                b: Any::new(3_i64),
+                result: Any::new(1_i64),
+            },
+            TestCase {
+                name: "negative dividend".to_string(),
+                a: Any::new(-7),
+                b: Any::new(3),
+                result: Any::new(-1),
+            },
+            TestCase {
+                name: "negative divisor".to_string(),
+                a: Any::new(7),
+                b: Any::new(-3),
+                result: Any::new(1),
+            },
+            TestCase {
+                name: "u64".to_string(),
+                a: Any::new(10_u64),
+                b: Any::new(4_u64),
+                result: Any::new(2_u64),
+            },
+            TestCase {
+                name: "f64".to_string(),
+                a: Any::new(5.5),
+                b: Any::new(2.0),
+                result: Any::new(1.5),
+            },
+            TestCase {
+                name: "i32 and f32".to_string(),
+                a: Any::new(5),
+                b: Any::new(1.5_f32),
+                result: Any::new(0.5),
+            },
+            TestCase {
+                name: "i64::MIN and -1".to_string(),
+                a: Any::new(i64::MIN),
+                b: Any::new(-1),
+                result: Any::new(0_i64),
+            },
+            TestCase {
+                name: "null".to_string(),
+                a: Any::new(5),
+                b: Any::from(_null),
+                result: Any::from(_null),
+            },
+        ];
+
+        for test_case in test_cases {
+            let result = test_case.a % test_case.b;
+            assert_eq!(result, test_case.result, "TC: {}", test_case.name);
+        }
+
+        assert!((Any::new(5) % Any::new(0)).is_nan());
+        assert!((Any::new(5) % Any::new(false)).is_nan());
+        assert!((Any::new(5.0) % Any::new(0.0)).is_nan());
+        assert!((Any::new(f64::NAN) % Any::new(2)).is_nan());
+        assert!((Any::new(f64::INFINITY) % Any::new(2)).is_nan());
+        assert!((Any::new("5") % Any::new(2)).is_nan());
+        assert_eq!(Any::new(5) % Any::new(f64::INFINITY), Any::new(5.0));
+    }
+
+    #[test]
+    fn test_rem_assign() {
+        let mut a = Any::new(7_i64);
+        a %= Any::new(4_i64);
+        assert_eq!(a, Any::new(3_i64));
+    }
+}
+
+/// JavaScript `**`, which differs from `powf` for an exponent of NaN or a base of ±1 with an infinite exponent.
+fn js_pow(base: f64, exponent: f64) -> f64 {
+    if exponent.is_nan() || (base.abs() == 1.0 && exponent.is_infinite()) {
+        f64::NAN
+    } else {
+        base.powf(exponent)
+    }
+}
+
+/// ToUint32 in JavaScript: the number is truncated and wrapped modulo 2^32. NaN and infinities become 0.
+fn to_uint32(value: &Any) -> u32 {
+    match &value.value {
+        Value::Int(int) => int.to_i128() as u32,
+        _ => {
+            let float = value.to_float();
+            if float.is_finite() {
+                float.trunc().rem_euclid(4294967296.0) as u32
+            } else {
+                0
+            }
+        }
+    }
+}
+
+/// ToInt32 in JavaScript.
+fn to_int32(value: &Any) -> i32 {
+    to_uint32(value) as i32
+}
+
+/// Implements the operators that have no Rust trait.
+impl Any {
+    /// Raises the value to the power of the exponent, like `**` in JavaScript.
+    ///
+    /// Integers follow the same promotion rules as the arithmetic operators.
+    /// A negative exponent or a result that does not fit in the promoted type gives a float.
+    /// A BigInt panics unless both operands are BigInts and the exponent is not negative.
+    /// ```
+    /// use anyrust::*;
+    ///
+    /// assert_eq!(any(2).pow(10), any(1024));
+    /// assert_eq!(any(2_u8).pow(10_u8), any(1024.0));
+    /// assert_eq!(any(2).pow(-1), any(0.5));
+    /// assert_eq!(any(4.0).pow(0.5), any(2.0));
+    /// ```
+    pub fn pow(&self, exponent: impl Into<Any>) -> Any {
+        let exponent = exponent.into();
+
+        match (&self.value, &exponent.value) {
+            (Value::Null, Value::Undefined) | (Value::Undefined, Value::Null) => Any::new(f64::NAN),
+            (Value::Null, _) | (_, Value::Null) => Any::new(_null),
+            (Value::BigInt(_), _) | (_, Value::BigInt(_)) => bigint::bigint_pow(self, &exponent),
+            (Value::Str(_), _) | (_, Value::Str(_)) => Any::new(f64::NAN),
+            _ => match numeric_operands(self, &exponent) {
+                Some(Operands::Int(int, base, exponent)) => {
+                    let checked = u32::try_from(exponent)
+                        .ok()
+                        .and_then(|exponent| base.checked_pow(exponent))
+                        .and_then(|value| int.checked_with(value));
+
+                    match checked {
+                        Some(int) => Any {
+                            value: Value::Int(int),
+                        },
+                        None => Any::new(js_pow(base as f64, exponent as f64)),
+                    }
+                }
+                Some(Operands::Decimal(base, exponent)) => {
+                    // a fractional power is not exact in general, so it is computed in f64
+                    match exponent.is_integer().then(|| exponent.to_i64()).flatten() {
+                        Some(exponent) => decimal::decimal_pow(base, exponent),
+                        None => Any::new(js_pow(base.to_float(), exponent.to_float())),
+                    }
+                }
+                Some(Operands::Float(base, exponent)) => Any::new(js_pow(base, exponent)),
+                None => Any::new(f64::NAN),
+            },
+        }
+    }
+
+    /// Unsigned right shift, like `>>>` in JavaScript.
+    ///
+    /// Both operands are converted with ToUint32 and the shift count is taken modulo 32.
+    /// ```
+    /// use anyrust::*;
+    ///
+    /// assert_eq!(any(-1).ushr(28), any(15_i64));
+    /// assert_eq!(any(-1).ushr(0), any(4294967295_i64));
+    /// ```
+    pub fn ushr(&self, other: impl Into<Any>) -> Any {
+        let other = other.into();
+
+        match (&self.value, &other.value) {
+            (Value::Null, Value::Undefined) | (Value::Undefined, Value::Null) => Any::new(0_i64),
+            (Value::Null, _) | (_, Value::Null) => Any::new(_null),
+            _ => Any::new((to_uint32(self) >> (to_uint32(&other) & 31)) as i64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_pow_for_any {
+    use super::*;
+
+    #[test]
+    fn test_pow() {
+        struct TestCase {
+            name: String,
+            a: Any,
+            b: Any,
+            result: Any,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                name: "i64".to_string(),
+                a: Any::new(3_i64),
+                b: Any::new(4_i64),
+                result: Any::new(81_i64),
+            },
+            TestCase {
+                name: "u8".to_string(),
+                a: Any::new(2_u8),
+                b: Any::new(10_u8),
+                result: Any::new(1024.0),
+            },
+            TestCase {
+                name: "zero exponent".to_string(),
+                a: Any::new(0),
+                b: Any::new(0),
+                result: Any::new(1),
+            },
+            TestCase {
+                name: "negative exponent".to_string(),
+                a: Any::new(2),
+                b: Any::new(-2),
+                result: Any::new(0.25),
+            },
+            TestCase {
+                name: "overflow".to_string(),
+                a: Any::new(2),
+                b: Any::new(64),
+                result: Any::new(18446744073709551616.0),
+            },
+            TestCase {
+                name: "f64".to_string(),
+                a: Any::new(2.0),
+                b: Any::new(0.5),
+                result: Any::new(2.0_f64.sqrt()),
+            },
+            TestCase {
+                name: "null".to_string(),
+                a: Any::new(2),
+                b: Any::from(_null),
+                result: Any::from(_null),
+            },
+        ];
+
+        for test_case in test_cases {
+            let result = test_case.a.pow(test_case.b);
+            assert_eq!(result, test_case.result, "TC: {}", test_case.name);
+        }
+
+        assert!(any(1).pow(f64::NAN).is_nan());
+        assert!(any(1).pow(f64::INFINITY).is_nan());
+        assert!(any(-1.0).pow(f64::NEG_INFINITY).is_nan());
+        assert!(any("2").pow(2).is_nan());
+        assert_eq!(any(f64::NAN).pow(0), any(1.0));
+    }
+}
+
+macro_rules! impl_bitwise_operator {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $op:tt) => {
+        /// Converts both operands with ToInt32 like JavaScript, so floats are truncated and values wrap at 32 bits.
+        impl $trait for Any {
+            type Output = Self;
+
+            fn $method(self, other: Self) -> Self {
+                match (&self.value, &other.value) {
+                    (Value::Null, Value::Undefined) | (Value::Undefined, Value::Null) => Any::new(0_i64),
+                    (Value::Null, _) | (_, Value::Null) => Any::new(_null),
+                    _ => Any::new((to_int32(&self) $op to_int32(&other)) as i64),
+                }
+            }
+        }
+
+        impl $assign_trait for Any {
+            fn $assign_method(&mut self, other: Self) {
+                *self = self.clone() $op other;
+            }
+        }
+    };
+}
+
+impl_bitwise_operator!(BitAnd, bitand, BitAndAssign, bitand_assign, &);
+impl_bitwise_operator!(BitOr, bitor, BitOrAssign, bitor_assign, |);
+impl_bitwise_operator!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^);
+
+#[cfg(test)]
+mod test_bitwise_for_any {
+    use super::*;
+
+    #[test]
+    fn test_bitwise() {
+        struct TestCase {
+            name: String,
+            a: Any,
+            b: Any,
+            and: Any,
+            or: Any,
+            xor: Any,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                name: "i64".to_string(),
+                a: Any::new(12_i64),
+                b: Any::new(10_i64),
+                and: Any::new(8_i64),
+                or: Any::new(14_i64),
+                xor: Any::new(6_i64),
+            },
+            TestCase {
+                name: "negative".to_string(),
+                a: Any::new(-1),
+                b: Any::new(5_u8),
+                and: Any::new(5_i64),
+                or: Any::new(-1_i64),
+                xor: Any::new(-6_i64),
+            },
+            TestCase {
+                name: "f64 is truncated".to_string(),
+                a: Any::new(5.9),
+                b: Any::new(-3.7),
+                and: Any::new(5_i64),
+                or: Any::new(-3_i64),
+                xor: Any::new(-8_i64),
+            },
+            TestCase {
+                name: "wraps at 32 bits".to_string(),
+                a: Any::new(4294967297_i64),
+                b: Any::new(2147483648_u64),
+                and: Any::new(0_i64),
+                or: Any::new(-2147483647_i64),
+                xor: Any::new(-2147483647_i64),
+            },
+            TestCase {
+                name: "NaN is 0".to_string(),
+                a: Any::new(f64::NAN),
+                b: Any::new(7),
+                and: Any::new(0_i64),
+                or: Any::new(7_i64),
+                xor: Any::new(7_i64),
+            },
+            TestCase {
+                name: "numeric string".to_string(),
+                a: Any::new("6"),
+                b: Any::new(true),
+                and: Any::new(0_i64),
+                or: Any::new(7_i64),
+                xor: Any::new(7_i64),
+            },
+            TestCase {
+                name: "null".to_string(),
+                a: Any::new(6),
+                b: Any::from(_null),
+                and: Any::from(_null),
+                or: Any::from(_null),
+                xor: Any::from(_null),
+            },
+        ];
+
+        for test_case in test_cases {
+            let (a, b) = (test_case.a, test_case.b);
+            assert_eq!(
+                a.clone() & b.clone(),
+                test_case.and,
+                "TC: {} &",
+                test_case.name
+            );
+            assert_eq!(
+                a.clone() | b.clone(),
+                test_case.or,
+                "TC: {} |",
+                test_case.name
+            );
+            assert_eq!(a ^ b, test_case.xor, "TC: {} ^", test_case.name);
+        }
+    }
+
+    #[test]
+    fn test_bitwise_assign() {
+        let mut a = Any::new(12);
+        a &= Any::new(10);
+        assert_eq!(a, Any::new(8_i64));
+
+        a |= Any::new(1);
+        assert_eq!(a, Any::new(9_i64));
+
+        a ^= Any::new(3);
+        assert_eq!(a, Any::new(10_i64));
+    }
+
+    #[test]
+    fn test_ushr() {
+        assert_eq!(any(16).ushr(2), any(4_i64));
+        assert_eq!(any(-16).ushr(2), any(1073741820_i64));
+        assert_eq!(any(1).ushr(33), any(0_i64));
+        assert_eq!(any(-1.5).ushr(0), any(4294967295_i64));
+        assert!(any(1).ushr(_null).is_null());
+    }
+}
+
+impl Not for Any {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        match self.value {
+            Value::Null => Any::new(_null),
+            _ => Any::new(!self.to_boolean()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_not_for_any {
+    use super::*;
+
+    #[test]
+    fn test_not() {
+        struct TestCase {
+            name: String,
+            a: Any,
+            result: Any,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                name: "true".to_string(),
+                a: Any::new(true),
+                result: Any::new(false),
+            },
+            TestCase {
+                name: "false".to_string(),
+                a: Any::new(false),
+                result: Any::new(true),
+            },
+            TestCase {
+                name: "zero value".to_string(),
+                a: Any::new(0),
+                result: Any::new(true),
+            },
+            TestCase {
+                name: "non zero value".to_string(),
+                a: Any::new(4444),
+                result: Any::new(false),
+            },
+        ];
+
+        for test_case in test_cases {
+            let result = !test_case.a;
+            assert_eq!(result, test_case.result, "TC: {}", test_case.name);
+        }
+    }
+}
+
+/// Compares values with SameValueZero, the equality of JavaScript `Map` keys and `includes`.
+///
+/// Integers, floats and Decimals are equal if their exact values are equal, whatever their types (`5_i32 == 5.0`).
+/// NaN is equal to NaN, which keeps `Eq` lawful for hash keys, and `0.0` is equal to `-0.0`.
+/// Other values are equal only if they have the same type and the same contents.
+/// See [`Any::strict_eq`], [`Any::loose_eq`] and [`Any::same_value`] for the JavaScript operators.
+impl PartialEq for Any {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.value, &other.value) {
+            (
+                Value::Int(_) | Value::Float(_) | Value::Decimal(_),
+                Value::Int(_) | Value::Float(_) | Value::Decimal(_),
+            ) => numeric_eq(self, other) || (self.is_nan() && other.is_nan()),
+            (Value::BigInt(a), Value::BigInt(b)) => a == b,
+            (Value::Str(a), Value::Str(b)) => a == b,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Array(a), Value::Array(b)) => a == b,
+            (Value::Map(a), Value::Map(b)) => a == b,
+            (Value::Set(a), Value::Set(b)) => a == b,
+            (Value::Null, Value::Null) => true,
+            (Value::Undefined, Value::Undefined) => true,
+            (Value::Pair(a), Value::Pair(b)) => a == b,
+            (Value::Function(a), Value::Function(b)) => a == b,
+            (Value::Custom(a), Value::Custom(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Any {}
+
+/// Compares two numbers by their exact value. NaN is not equal to anything.
+fn numeric_eq(a: &Any, b: &Any) -> bool {
+    match (a.to_number(), b.to_number()) {
+        (Some(a), Some(b)) => compare_numbers(a, b) == Some(Ordering::Equal),
+        _ => false,
+    }
+}
+
+/// canonical form of a number for hashing, so that equal numbers hash the same whatever their types
+#[derive(Hash)]
+enum NumberKey {
+    /// an integral value, from any number type
+    Integer(i128),
+    /// a fraction or a non-finite float, or a Decimal that is exactly this float
+    Float(u64),
+    /// a Decimal fraction that no float is equal to
+    Decimal(Decimal),
+}
+
+impl NumberKey {
+    fn new(value: &Value) -> Option<Self> {
+        match value {
+            Value::Int(int) => Some(NumberKey::Integer(int.to_i128())),
+            Value::Float(float) => Some(NumberKey::from_float(float.to_f64())),
+            Value::Decimal(decimal) if decimal.fract().is_zero() => {
+                decimal.to_i128().map(NumberKey::Integer)
+            }
+            Value::Decimal(decimal) => Some(match decimal.to_f64() {
+                Some(float)
+                    if decimal::compare_decimal_float(*decimal, float) == Some(Ordering::Equal) =>
+                {
+                    NumberKey::Float(float.to_bits())
+                }
+                _ => NumberKey::Decimal(decimal.normalize()),
+            }),
+            _ => None,
+        }
+    }
+
+    fn from_float(float: f64) -> Self {
+        if float.is_nan() {
+            // every NaN is equal to every other
+            NumberKey::Float(f64::NAN.to_bits())
+        } else if float.fract() == 0.0 && float.abs() < i128::MAX as f64 {
+            // -0.0 becomes 0
+            NumberKey::Integer(float as i128)
+        } else {
+            NumberKey::Float(float.to_bits())
+        }
+    }
+}
+
+/// Returns true for the values that are objects in JavaScript. (arrays, maps, pairs, functions and user types)
+fn is_object_like(value: &Value) -> bool {
+    matches!(
+        value,
+        Value::Array(_)
+            | Value::Map(_)
+            | Value::Set(_)
+            | Value::Pair(_)
+            | Value::Function(_)
+            | Value::Custom(_)
+    )
+}
+
+/// Implements the JavaScript equality operators.
+///
+/// Values in Any have no identity, so arrays, maps and other objects are compared by their contents
+/// where JavaScript would compare references.
+impl Any {
+    /// Strict equality, like `===` in JavaScript.
+    ///
+    /// Numbers compare by value across integer, float and Decimal types, and NaN is not equal to itself.
+    /// Values of different kinds are never equal.
+    /// ```
+    /// use anyrust::*;
+    ///
+    /// assert!(any(5_i32).strict_eq(&any(5.0)));
+    /// assert!(any(0.0).strict_eq(&any(-0.0)));
+    /// assert!(!any(f64::NAN).strict_eq(&any(f64::NAN)));
+    /// assert!(!any(5).strict_eq(&any("5")));
+    /// ```
+    pub fn strict_eq(&self, other: &Any) -> bool {
+        if self.is_number() && other.is_number() {
+            numeric_eq(self, other)
+        } else {
+            self == other
+        }
+    }
+
+    /// Loose equality, like `==` in JavaScript.
+    ///
+    /// null and undefined are only equal to each other. Booleans are converted to numbers, and a string compared with a number
+    /// (or a BigInt) is converted to one. An object compared with a primitive is converted to its string form.
+    /// ```
+    /// use anyrust::*;
+    ///
+    /// assert!(any(5).loose_eq(&any("5")));
+    /// assert!(any(1).loose_eq(&any(true)));
+    /// assert!(any("").loose_eq(&any(0)));
+    /// assert!(array![1, 2].loose_eq(&any("[1, 2]")));
+    /// assert!(!null.loose_eq(&any(0)));
+    /// assert!(null.loose_eq(&undefined));
+    /// assert!(!any(f64::NAN).loose_eq(&any(f64::NAN)));
+    /// ```
+    pub fn loose_eq(&self, other: &Any) -> bool {
+        match (&self.value, &other.value) {
+            (Value::Null | Value::Undefined, Value::Null | Value::Undefined) => true,
+            (Value::Null | Value::Undefined, _) | (_, Value::Null | Value::Undefined) => false,
+            (Value::Str(a), Value::Str(b)) => a == b,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Bool(a), _) => Any::new(*a as i32).loose_eq(other),
+            (_, Value::Bool(b)) => self.loose_eq(&Any::new(*b as i32)),
+            (Value::BigInt(a), Value::Str(b)) | (Value::Str(b), Value::BigInt(a)) => {
+                bigint::parse_bigint(b).as_ref() == Some(&**a)
+            }
+            (a, b) if is_object_like(a) && is_object_like(b) => self == other,
+            (a, _) if is_object_like(a) => Any::new(self.to_str()).loose_eq(other),
+            (_, b) if is_object_like(b) => self.loose_eq(&Any::new(other.to_str())),
+            _ => {
+                compare_numbers(
+                    self.to_primitive().into_number(),
+                    other.to_primitive().into_number(),
+                ) == Some(Ordering::Equal)
+            }
+        }
+    }
+
+    /// SameValue equality, like `Object.is` in JavaScript.
+    ///
+    /// Same as [`Any::strict_eq`], except that NaN is equal to NaN and `0.0` is not equal to `-0.0`.
+    /// ```
+    /// use anyrust::*;
+    ///
+    /// assert!(any(f64::NAN).same_value(&any(f64::NAN)));
+    /// assert!(!any(0.0).same_value(&any(-0.0)));
+    /// assert!(any(0).same_value(&any(0.0)));
+    /// ```
+    pub fn same_value(&self, other: &Any) -> bool {
+        let is_negative_zero = |value: &Any| matches!(&value.value, Value::Float(float) if float.to_f64() == 0.0 && float.to_f64().is_sign_negative());
+
+        is_negative_zero(self) == is_negative_zero(other) && self == other
+    }
+}
+
+#[cfg(test)]
+mod test_eq_for_any {
+    use super::*;
+
+    #[test]
+    fn test_eq() {
+        let a = Any::new(5);
+        let b = Any::new(5);
+        assert_eq!(a, b);
+
+        let a = Any::new(5);
+        let b = Any::new(10);
+        assert_ne!(a, b);
+
+        let a = Any::new(5);
+        let b = Any::new(5.0);
+        assert_eq!(a, b);
+
+        let a = Any::new(5_i32);
+        let b = Any::new(5_i64);
+        assert_eq!(a, b);
+
+        let a = Any::new(f64::NAN);
+        let b = Any::new(f32::NAN);
+        assert_eq!(a, b);
+
+        let a = Any::new(0.1_f32);
+        let b = Any::new(0.1_f64);
+        assert_ne!(a, b);
+
+        let a = Any::new(u64::MAX);
+        let b = Any::new(u64::MAX as f64);
+        assert_ne!(a, b);
+
+        let a = Any::new(5);
+        let b = Any::new("5");
+        assert_ne!(a, b);
+
+        let a = Any::new(5);
+        let b = Any::new("5");
+        assert_ne!(a, b);
+
+        let a = Any::new(5);
+        let b = Any::new(true);
+        assert_ne!(a, b);
+
+        let a = Any::new(5);
+        let b = Any::new(false);
+        assert_ne!(a, b);
+
+        let a = Any::new(5);
+        let b = Any::from(vec![1, 2, 3]);
+        assert_ne!(a, b);
+
+        let a = Any::new(5);
+        let b = Any::from(HashMap::new());
+        assert_ne!(a, b);
+
+        let a = Any::new(5);
+        let b = Any::new(_null);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_hash_matches_eq() {
+        use std::{collections::HashSet, hash::Hasher};
+
+        let numbers = vec![
+            Any::new(5_u8),
+            Any::new(5_i64),
+            Any::new(5.0_f32),
+            Any::new(5.0),
+            Any::from(Decimal::new(500, 2)),
+            Any::new(0.0),
+            Any::new(-0.0),
+            Any::new(f64::NAN),
+            Any::new(f32::NAN),
+            Any::new(2.5),
+            Any::from(Decimal::new(25, 1)),
+        ];
+        let set: HashSet<Any> = numbers.into_iter().collect();
+        assert_eq!(set.len(), 4);
+
+        // fractions with the same integral part do not collide
+        let hash_of = |value: Any| {
+            let mut hasher = std::hash::DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_ne!(hash_of(Any::new(0.25)), hash_of(Any::new(0.5)));
+        assert_ne!(hash_of(Any::new(1.25)), hash_of(Any::new(1.5)));
+        assert_ne!(
+            hash_of(Any::from(Decimal::new(1, 1))),
+            hash_of(Any::from(Decimal::new(2, 1)))
+        );
+        assert_eq!(
+            hash_of(Any::new(0.5_f32)),
+            hash_of(Any::from(Decimal::new(5, 1)))
+        );
+        assert_eq!(
+            hash_of(Any::new(3.0)),
+            hash_of(Any::from(Decimal::new(300, 2)))
+        );
+
+        let mut map = Map::new();
+        map.set(1, "one");
+        map.set(1.0, "uno");
+        assert_eq!(map.length(), 1);
+        assert_eq!(map.get(&Any::new(1_u8)), Some(&Any::new("uno")));
+    }
+
+    #[test]
+    fn test_strict_eq() {
+        assert!(Any::new(5_i32).strict_eq(&Any::new(5_u64)));
+        assert!(Any::new(5).strict_eq(&Any::from(Decimal::new(50, 1))));
+        assert!(Any::new(-0.0).strict_eq(&Any::new(0)));
+        assert!(!Any::new(f64::NAN).strict_eq(&Any::new(f64::NAN)));
+        assert!(!Any::new(1).strict_eq(&Any::new(true)));
+        assert!(!Any::new(1).strict_eq(&Any::from(1_i128)));
+        assert!(Any::new("a").strict_eq(&Any::new("a")));
+        assert!(Any::new(_null).strict_eq(&Any::new(_null)));
+    }
+
+    #[test]
+    fn test_loose_eq() {
+        struct TestCase {
+            name: String,
+            a: Any,
+            b: Any,
+            result: bool,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                name: "number == string".to_string(),
+                a: Any::new(5),
+                b: Any::new(" 5 "),
+                result: true,
+            },
+            TestCase {
+                name: "empty string == 0".to_string(),
+                a: Any::new(""),
+                b: Any::new(0),
+                result: true,
+            },
+            TestCase {
+                name: "true == 1".to_string(),
+                a: Any::new(true),
+                b: Any::new(1.0),
+                result: true,
+            },
+            TestCase {
+                name: "true == '1'".to_string(),
+                a: Any::new(true),
+                b: Any::new("1"),
+                result: true,
+            },
+            TestCase {
+                name: "true == 'true'".to_string(),
+                a: Any::new(true),
+                b: Any::new("true"),
+                result: false,
+            },
+            TestCase {
+                name: "null == 0".to_string(),
+                a: Any::new(_null),
+                b: Any::new(0),
+                result: false,
+            },
+            TestCase {
+                name: "null == null".to_string(),
+                a: Any::new(_null),
+                b: Any::new(_null),
+                result: true,
+            },
+            TestCase {
+                name: "NaN == NaN".to_string(),
+                a: Any::new(f64::NAN),
+                b: Any::new(f64::NAN),
+                result: false,
+            },
+            TestCase {
+                name: "bigint == number".to_string(),
+                a: Any::from(2_i128),
+                b: Any::new(2.0),
+                result: true,
+            },
+            TestCase {
+                name: "bigint == string".to_string(),
+                a: Any::from(2_i128),
+                b: Any::new("2"),
+                result: true,
+            },
+            TestCase {
+                name: "bigint == fractional string".to_string(),
+                a: Any::from(2_i128),
+                b: Any::new("2.0"),
+                result: false,
+            },
+            TestCase {
+                name: "array == string".to_string(),
+                a: Any::from(vec![1, 2]),
+                b: Any::new("[1, 2]"),
+                result: true,
+            },
+            TestCase {
+                name: "array == array".to_string(),
+                a: Any::from(vec![1, 2]),
+                b: Any::from(vec![1, 2]),
+                result: true,
+            },
+        ];
+
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.a.loose_eq(&test_case.b),
+                test_case.result,
+                "TC: {}",
+                test_case.name
+            );
+            assert_eq!(
+                test_case.b.loose_eq(&test_case.a),
+                test_case.result,
+                "TC: {} (reversed)",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_same_value() {
+        assert!(Any::new(f64::NAN).same_value(&Any::new(f32::NAN)));
+        assert!(!Any::new(0.0).same_value(&Any::new(-0.0)));
+        assert!(!Any::new(-0.0).same_value(&Any::new(0)));
+        assert!(Any::new(-0.0).same_value(&Any::new(-0.0_f32)));
+        assert!(Any::new(3_u8).same_value(&Any::new(3.0)));
+        assert!(!Any::new(3).same_value(&Any::new("3")));
+    }
+}
+
+/// Consistent with `PartialEq`: equal numbers hash the same whatever their types.
+impl Hash for Any {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        if !self.is_number() {
+            self.type_id().hash(state);
+        }
+        match &self.value {
+            Value::Int(_) | Value::Float(_) | Value::Decimal(_) => {
+                NumberKey::new(&self.value).hash(state)
+            }
+            Value::BigInt(bigint) => bigint.hash(state),
+            Value::Str(string) => string.hash(state),
+            Value::Bool(boolean) => boolean.hash(state),
+            Value::Array(array) => array.0.hash(state),
+            Value::Map(map) => map.hash(state),
+            Value::Set(set) => set.hash(state),
+            Value::Pair(pair) => pair.hash(state),
+            Value::Function(function) => function.hash(state),
+            Value::Null | Value::Undefined => {}
+            Value::Custom(custom) => (Arc::as_ptr(custom) as *const () as usize).hash(state),
+        }
+    }
+}
+
+/// number used by the relational comparison
+#[derive(Debug, Clone)]
+enum Number {
+    Int(i128),
+    Float(f64),
+    Big(BigInt),
+    Decimal(Decimal),
+}
+
+impl Number {
+    fn is_nan(&self) -> bool {
+        matches!(self, Number::Float(float) if float.is_nan())
+    }
+}
+
+/// Compares two numbers exactly, even across integer and float types. Returns None if either is NaN.
+fn compare_numbers(a: Number, b: Number) -> Option<Ordering> {
+    match (a, b) {
+        (Number::Int(a), Number::Int(b)) => Some(a.cmp(&b)),
+        (Number::Float(a), Number::Float(b)) => a.partial_cmp(&b),
+        (Number::Int(a), Number::Float(b)) => compare_int_float(a, b),
+        (Number::Float(a), Number::Int(b)) => compare_int_float(b, a).map(Ordering::reverse),
+        (Number::Big(a), Number::Big(b)) => Some(a.cmp(&b)),
+        (Number::Big(a), Number::Int(b)) => Some(a.cmp(&BigInt::from(b))),
+        (Number::Int(a), Number::Big(b)) => Some(BigInt::from(a).cmp(&b)),
+        (Number::Big(a), Number::Float(b)) => bigint::compare_bigint_float(&a, b),
+        (Number::Float(a), Number::Big(b)) => {
+            bigint::compare_bigint_float(&b, a).map(Ordering::reverse)
+        }
+        (Number::Decimal(a), Number::Decimal(b)) => Some(a.cmp(&b)),
+        // every Int fits in a Decimal
+        (Number::Decimal(a), Number::Int(b)) => Decimal::from_i128(b).map(|b| a.cmp(&b)),
+        (Number::Int(a), Number::Decimal(b)) => Decimal::from_i128(a).map(|a| a.cmp(&b)),
+        (Number::Decimal(a), Number::Float(b)) => decimal::compare_decimal_float(a, b),
+        (Number::Float(a), Number::Decimal(b)) => {
+            decimal::compare_decimal_float(b, a).map(Ordering::reverse)
+        }
+        (Number::Decimal(a), Number::Big(b)) => Some(decimal::compare_decimal_bigint(a, &b)),
+        (Number::Big(a), Number::Decimal(b)) => {
+            Some(decimal::compare_decimal_bigint(b, &a).reverse())
+        }
+    }
+}
+
+fn compare_int_float(a: i128, b: f64) -> Option<Ordering> {
+    match (a as f64).partial_cmp(&b)? {
+        // b is integral here, so it converts to i128 without loss
+        Ordering::Equal => Some(a.cmp(&(b as i128))),
+        ordering => Some(ordering),
+    }
+}
+
+/// primitive value of Any, used by the relational comparison (ToPrimitive)
+enum Primitive<'a> {
+    Number(Number),
+    Str(Cow<'a, str>),
+}
+
+impl Any {
+    fn to_primitive(&self) -> Primitive<'_> {
+        match &self.value {
+            Value::Int(int) => Primitive::Number(Number::Int(int.to_i128())),
+            Value::Float(float) => Primitive::Number(Number::Float(float.to_f64())),
+            Value::BigInt(bigint) => Primitive::Number(Number::Big((**bigint).clone())),
+            Value::Decimal(decimal) => Primitive::Number(Number::Decimal(*decimal)),
+            Value::Bool(boolean) => Primitive::Number(Number::Int(*boolean as i128)),
+            Value::Null => Primitive::Number(Number::Int(0)),
+            Value::Undefined => Primitive::Number(Number::Float(f64::NAN)),
+            Value::Str(string) => Primitive::Str(Cow::Borrowed(string)),
+            _ => Primitive::Str(Cow::Owned(self.to_str())),
+        }
+    }
+
+    fn to_number(&self) -> Option<Number> {
+        match &self.value {
+            Value::Int(int) => Some(Number::Int(int.to_i128())),
+            Value::Float(float) => Some(Number::Float(float.to_f64())),
+            Value::BigInt(bigint) => Some(Number::Big((**bigint).clone())),
+            Value::Decimal(decimal) => Some(Number::Decimal(*decimal)),
+            _ => None,
+        }
+    }
+}
+
+impl Primitive<'_> {
+    fn into_number(self) -> Number {
+        match self {
+            Primitive::Number(number) => number,
+            Primitive::Str(string) => Number::Float(string.to_string().to_float()),
+        }
+    }
+}
+
+/// Compares two values like the JavaScript relational operators (`<`, `<=`, `>`, `>=`), in a way that agrees with `==`.
+///
+/// Arrays, maps and other objects are converted to their string form first.
+/// If both sides are strings they are compared lexicographically, otherwise both sides are converted to numbers.
+/// Comparing with NaN returns None, so every relational operator returns false.
+///
+/// Values that are `==` are Equal, so NaN is Equal to NaN. Values that JavaScript would find equal after the conversion,
+/// but that are not `==` (such as `"10"` and `10`, or null and `0`), return None.
+/// Use [`Any::js_lt`], [`Any::js_le`], [`Any::js_gt`] and [`Any::js_ge`] for the exact JavaScript operators.
+/**
+```
+use anyrust::*;
+
+assert!(any(1) < any(2.5));
+assert!(any("apple") < any("banana"));
+assert!(any("10") > any(9));
+assert_eq!(any(f64::NAN).partial_cmp(&any(1)), None);
+assert_eq!(any("10").partial_cmp(&any(10)), None);
+```
+*/
+impl PartialOrd for Any {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self == other {
+            return Some(Ordering::Equal);
+        }
+
+        match self.js_compare(other) {
+            Some(Ordering::Equal) => None,
+            ordering => ordering,
+        }
+    }
+}
+
+/// Implements the JavaScript relational operators.
+impl Any {
+    fn js_compare(&self, other: &Any) -> Option<Ordering> {
+        match (self.to_primitive(), other.to_primitive()) {
+            (Primitive::Str(a), Primitive::Str(b)) => Some(a.cmp(&b)),
+            (a, b) => compare_numbers(a.into_number(), b.into_number()),
+        }
+    }
+
+    /// `<` in JavaScript.
+    /// ```
+    /// use anyrust::*;
+    ///
+    /// assert!(any("9").js_lt(&any(10)));
+    /// assert!(!any(f64::NAN).js_lt(&any(1)));
+    /// ```
+    pub fn js_lt(&self, other: &Any) -> bool {
+        self.js_compare(other) == Some(Ordering::Less)
+    }
+
+    /// `<=` in JavaScript.
+    /// ```
+    /// use anyrust::*;
+    ///
+    /// assert!(null.js_le(&any(0)));
+    /// assert!(any("10").js_le(&any(10)));
+    /// assert!(!any(f64::NAN).js_le(&any(f64::NAN)));
+    /// ```
+    pub fn js_le(&self, other: &Any) -> bool {
+        matches!(
+            self.js_compare(other),
+            Some(Ordering::Less | Ordering::Equal)
+        )
+    }
+
+    /// `>` in JavaScript.
+    pub fn js_gt(&self, other: &Any) -> bool {
+        self.js_compare(other) == Some(Ordering::Greater)
+    }
+
+    /// `>=` in JavaScript.
+    pub fn js_ge(&self, other: &Any) -> bool {
+        matches!(
+            self.js_compare(other),
+            Some(Ordering::Greater | Ordering::Equal)
+        )
+    }
+}
+
+/// Implements a total order over all values.
+impl Any {
+    /// Compares two values with a total order, for sorting and ordered collections.
+    ///
+    /// Values of different kinds are ordered as null < boolean < number < string < array < map < set < pair < function < object < undefined.
+    /// undefined sorts last, like in `Array.prototype.sort`.
+    /// Arrays and pairs compare their elements in order. Maps compare their entries sorted by key, and sets their sorted values.
+    /// Numbers compare numerically across integer and float types, and NaN is greater than every other number.
+    /// A BigInt is not `==` to other number types, so it sorts right after an equal number of another type.
+    /// Functions and objects that are not `==` are ordered by address, which is consistent but not stable across runs.
+    /**
+    ```
+    use anyrust::*;
+
+    let mut values = vec![any("b"), any(f64::NAN), any(2), null.clone(), any(1.5), any("a")];
+    values.sort_by(Any::cmp_total);
+
+    let sorted = values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+    assert_eq!(sorted, ["null", "1.5", "2", "NaN", "a", "b"]);
+    ```
+    */
+    pub fn cmp_total(&self, other: &Self) -> Ordering {
+        match (&self.value, &other.value) {
+            (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+            (Value::Str(a), Value::Str(b)) => a.cmp(b),
+            (Value::Array(a), Value::Array(b)) => {
+                cmp_total_iter(a.0.iter(), b.0.iter(), |a, b| a.cmp_total(b))
+            }
+            // sorted by key, so that the order of the entries does not matter, like in ==
+            (Value::Map(a), Value::Map(b)) => {
+                let (a, b) = (sorted_entries(a), sorted_entries(b));
+                cmp_total_iter(a.into_iter(), b.into_iter(), |a, b| {
+                    a.0.cmp_total(b.0).then_with(|| a.1.cmp_total(b.1))
+                })
             }
-        } else if self.type_id == *STRING || other.type_id == *STRING {
-            Any::new(f64::NAN)
-        } else if self.type_id == *STR || other.type_id == *STR {
-            Any::new(f64::NAN)
-        } else if self.type_id == *F64 || other.type_id == *F64 {
-            let a: f64 = self.data.to_float();
-            let b = other.data.to_float();
-            Any::new(a / b)
-        } else if self.type_id == *F32 || other.type_id == *F32 {
-            let a: f64 = self.data.to_float();
-            let b = other.data.to_float();
-            Any::new(a / b)
-        } else if self.type_id == *I64 || other.type_id == *I64 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a / b)
-        } else if self.type_id == *I32 || other.type_id == *I32 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a / b)
-        } else if self.type_id == *I16 || other.type_id == *I16 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a / b)
-        } else if self.type_id == *I8 || other.type_id == *I8 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            let result = a + b;
-            Any::new(result)
-        } else if self.type_id == *U64 || other.type_id == *U64 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a / b)
-        } else if self.type_id == *U32 || other.type_id == *U32 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a / b)
-        } else if self.type_id == *U16 || other.type_id == *U16 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a / b)
-        } else if self.type_id == *U8 || other.type_id == *U8 {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a / b)
-        } else {
-            Any::new(f64::NAN)
+            (Value::Set(a), Value::Set(b)) => {
+                let (a, b) = (sorted_values(a), sorted_values(b));
+                cmp_total_iter(a.into_iter(), b.into_iter(), |a, b| a.cmp_total(b))
+            }
+            (Value::Pair(a), Value::Pair(b)) => {
+                let (a, b) = (&a.0, &b.0);
+                a.0.cmp_total(&b.0).then_with(|| a.1.cmp_total(&b.1))
+            }
+            // functions are equal only to their clones, so they are ordered by address to agree with ==
+            (Value::Function(a), Value::Function(b)) => (Arc::as_ptr(&a.f) as *const () as usize)
+                .cmp(&(Arc::as_ptr(&b.f) as *const () as usize)),
+            // distinct objects with the same string form are ordered by address, to agree with ==
+            (Value::Custom(a), Value::Custom(b)) => {
+                self.to_str().cmp(&other.to_str()).then_with(|| {
+                    (Arc::as_ptr(a) as *const () as usize)
+                        .cmp(&(Arc::as_ptr(b) as *const () as usize))
+                })
+            }
+            _ => match (self.to_number(), other.to_number()) {
+                (Some(a), Some(b)) => match (a.is_nan(), b.is_nan()) {
+                    (true, true) => Ordering::Equal,
+                    (true, false) => Ordering::Greater,
+                    (false, true) => Ordering::Less,
+                    // a BigInt is never == to another number type, so it sorts after an equal one
+                    (false, false) => compare_numbers(a, b)
+                        .unwrap_or(Ordering::Equal)
+                        .then_with(|| self.is_bigint().cmp(&other.is_bigint())),
+                },
+                _ => total_rank(&self.value).cmp(&total_rank(&other.value)),
+            },
         }
     }
 }
 
-impl DivAssign for Any {
-    fn div_assign(&mut self, other: Self) {
-        *self = self.clone() / other;
+/// rank of each kind of value in [`Any::cmp_total`]
+fn total_rank(value: &Value) -> u8 {
+    match value {
+        Value::Null => 0,
+        Value::Bool(_) => 1,
+        Value::Int(_) | Value::Float(_) | Value::BigInt(_) | Value::Decimal(_) => 2,
+        Value::Str(_) => 3,
+        Value::Array(_) => 4,
+        Value::Map(_) => 5,
+        Value::Set(_) => 6,
+        Value::Pair(_) => 7,
+        Value::Function(_) => 8,
+        Value::Custom(_) => 9,
+        Value::Undefined => 10,
+    }
+}
+
+/// Entries of the map sorted by key with [`Any::cmp_total`].
+fn sorted_entries(map: &Map) -> Vec<(&Any, &Any)> {
+    let mut entries = map.0.iter().collect::<Vec<_>>();
+    entries.sort_by(|a, b| a.0.cmp_total(b.0));
+    entries
+}
+
+/// Values of the set sorted with [`Any::cmp_total`].
+fn sorted_values(set: &Set) -> Vec<&Any> {
+    let mut values = set.0.iter().collect::<Vec<_>>();
+    values.sort_by(|a, b| a.cmp_total(b));
+    values
+}
+
+/// Compares two sequences element by element, then by length.
+fn cmp_total_iter<T>(
+    mut a: impl Iterator<Item = T>,
+    mut b: impl Iterator<Item = T>,
+    cmp: impl Fn(T, T) -> Ordering,
+) -> Ordering {
+    loop {
+        match (a.next(), b.next()) {
+            (Some(a), Some(b)) => match cmp(a, b) {
+                Ordering::Equal => continue,
+                ordering => return ordering,
+            },
+            (Some(_), None) => return Ordering::Greater,
+            (None, Some(_)) => return Ordering::Less,
+            (None, None) => return Ordering::Equal,
+        }
     }
 }
 
-#[cfg(test)]
-mod test_div_for_any {
-    use super::*;
+/// wrapper that orders values with [`Any::cmp_total`]
+///
+/// `Any` itself only implements the JavaScript-style `PartialOrd`.
+/// Wrap it in `TotalOrd` to use it as a `BTreeMap` key or anywhere else `Ord` is required.
+/**
+```
+use anyrust::*;
+use std::collections::BTreeMap;
 
-    #[test]
-    fn test_div() {
-        struct TestCase {
-            name: String,
-            a: Any,
-            b: Any,
-            result: Any,
-        }
+let mut map = BTreeMap::new();
+map.insert(TotalOrd(any("b")), 2);
+map.insert(TotalOrd(any(1)), 1);
+map.insert(TotalOrd(any(1.0)), 3);
 
-        let test_cases = vec![
-            TestCase {
-                name: "i64".to_string(),
-                a: Any::new(5_i64),
-                b: Any::new(10_i64),
-                result: Any::new(0_i64),
-            },
-            TestCase {
-                name: "u64".to_string(),
-                a: Any::new(5_u64),
-                b: Any::new(10_u64),
-                result: Any::new(0_i64),
-            },
-            TestCase {
-                name: "f32".to_string(),
-                a: Any::new(5.0_f32),
-                b: Any::new(10.0_f32),
-                result: Any::new(0.5_f64),
-            },
-            TestCase {
-                name: "f64".to_string(),
-                a: Any::new(5.0),
-                b: Any::new(10.0),
-                result: Any::new(0.5),
-            },
-        ];
+assert_eq!(map.len(), 2);
+assert_eq!(map.keys().next().unwrap().0, any(1));
+assert_eq!(map[&TotalOrd(any(1))], 3);
+```
+*/
+#[derive(Debug, Clone)]
+pub struct TotalOrd(pub Any);
 
-        for test_case in test_cases {
-            let result = test_case.a / test_case.b;
-            assert_eq!(result, test_case.result, "TC: {}", test_case.name);
-        }
+impl From<Any> for TotalOrd {
+    fn from(value: Any) -> Self {
+        TotalOrd(value)
     }
+}
 
-    #[test]
-    fn test_div_assign() {
-        let mut a = Any::new(5_i64);
-        let b = Any::new(10_i64);
-        a /= b;
-        assert_eq!(a, Any::new(0_i64));
+impl PartialEq for TotalOrd {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
     }
 }
 
-impl Not for Any {
-    type Output = Self;
+impl Eq for TotalOrd {}
 
-    fn not(self) -> Self {
-        if self.type_id == *NULL {
-            Any::new(_null)
-        } else {
-            let a = self.data.to_boolean();
-            Any::new(!a)
-        }
+impl PartialOrd for TotalOrd {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TotalOrd {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp_total(&other.0)
     }
 }
 
 #[cfg(test)]
-mod test_not_for_any {
+mod test_ord_for_any {
     use super::*;
 
     #[test]
-    fn test_not() {
-        struct TestCase {
-            name: String,
-            a: Any,
-            result: Any,
-        }
+    fn test_partial_cmp_numbers() {
+        assert!(Any::new(1) < Any::new(2));
+        assert!(Any::new(2_u8) > Any::new(1_i64));
+        assert!(Any::new(1) < Any::new(1.5));
+        assert!(Any::new(1) <= Any::new(1.0));
+        assert!(Any::new(1) >= Any::new(1.0));
+        assert!(Any::new(u64::MAX) > Any::new(i64::MAX));
+        assert!(Any::new(9007199254740993_i64) > Any::new(9007199254740992.0));
+        assert!(Any::new(-0.0) >= Any::new(0));
+        assert!(Any::new(true) > Any::new(false));
+        assert!(Any::new(true) > Any::new(0.5));
+    }
 
-        let test_cases = vec![
-            TestCase {
-                name: "true".to_string(),
-                a: Any::new(true),
-                result: Any::new(false),
-            },
-            TestCase {
-                name: "false".to_string(),
-                a: Any::new(false),
-                result: Any::new(true),
-            },
-            TestCase {
-                name: "zero value".to_string(),
-                a: Any::new(0),
-                result: Any::new(true),
-            },
-            TestCase {
-                name: "non zero value".to_string(),
-                a: Any::new(4444),
-                result: Any::new(false),
-            },
-        ];
+    #[test]
+    fn test_partial_cmp_strings() {
+        assert!(Any::new("apple") < Any::new("banana"));
+        assert!(Any::new("a") < Any::new("ab"));
+        assert!(Any::new("B") < Any::new("a"));
+        // strings compare lexicographically, not numerically
+        assert!(Any::new("10") < Any::new("9"));
+        assert!(Any::new("abc") >= Any::new(String::from("abc")));
+    }
 
-        for test_case in test_cases {
-            let result = !test_case.a;
-            assert_eq!(result, test_case.result, "TC: {}", test_case.name);
-        }
+    #[test]
+    fn test_partial_cmp_mixed() {
+        // a string and a number compare as numbers
+        assert!(Any::new("10") > Any::new(9));
+        assert!(Any::new(" 2 ") < Any::new(3.5));
+        assert!(Any::new("") < Any::new(1));
+        assert!(Any::new(_null) < Any::new(1));
+
+        // equal after the conversion but not ==, so unordered
+        assert_eq!(Any::new("10").partial_cmp(&Any::new(10)), None);
+        assert_eq!(Any::new(_null).partial_cmp(&Any::new(0)), None);
+        assert!(!Any::new(_null).ge(&Any::new(0)));
     }
-}
 
-impl PartialEq for Any {
-    fn eq(&self, other: &Self) -> bool {
-        if self.type_id != other.type_id {
-            false
-        } else {
-            match self.type_id {
-                type_id if type_id == *I8 => self.data.to_integer() == other.data.to_integer(),
-                type_id if type_id == *I16 => self.data.to_integer() == other.data.to_integer(),
-                type_id if type_id == *I32 => self.data.to_integer() == other.data.to_integer(),
-                type_id if type_id == *I64 => self.data.to_integer() == other.data.to_integer(),
-                type_id if type_id == *ISIZE => self.data.to_integer() == other.data.to_integer(),
-                type_id if type_id == *U8 => self.data.to_integer() == other.data.to_integer(),
-                type_id if type_id == *U16 => self.data.to_integer() == other.data.to_integer(),
-                type_id if type_id == *U32 => self.data.to_integer() == other.data.to_integer(),
-                type_id if type_id == *U64 => self.data.to_integer() == other.data.to_integer(),
-                type_id if type_id == *USIZE => self.data.to_integer() == other.data.to_integer(),
-                type_id if type_id == *F32 => self.data.to_float() == other.data.to_float(),
-                type_id if type_id == *F64 => self.data.to_float() == other.data.to_float(),
-                type_id if type_id == *STRING => self.data.to_string() == other.data.to_string(),
-                type_id if type_id == *STR => self.data.to_string() == other.data.to_string(),
-                type_id if type_id == *BOOL => self.data.to_boolean() == other.data.to_boolean(),
-                type_id if type_id == *ARRAY => self.data.to_array() == other.data.to_array(),
-                type_id if type_id == *MAP => self.data.to_map() == other.data.to_map(),
-                _ => self.data.to_string() == other.data.to_string(),
+    #[test]
+    fn test_partial_cmp_agrees_with_eq() {
+        let values = [
+            Any::new(1),
+            Any::new(1.0),
+            Any::new("1"),
+            Any::new(true),
+            Any::new(0),
+            Any::new(_null),
+            Any::new(_undefined),
+            Any::new(f64::NAN),
+            Any::new(""),
+            Any::from(vec![1]),
+            Any::from(vec![1.0]),
+            Any::new("[1]"),
+        ];
+
+        for a in &values {
+            for b in &values {
+                assert_eq!(
+                    a.partial_cmp(b) == Some(Ordering::Equal),
+                    a == b,
+                    "TC: {a:?} {b:?}"
+                );
             }
         }
     }
-}
 
-impl Eq for Any {}
+    #[test]
+    fn test_js_relational() {
+        assert!(Any::new("10").js_le(&Any::new(10)));
+        assert!(Any::new("10").js_ge(&Any::new(10)));
+        assert!(Any::new(_null).js_ge(&Any::new(0)));
+        assert!(Any::new(_null).js_le(&Any::new(false)));
+        assert!(Any::new("10").js_lt(&Any::new("9")));
+        assert!(Any::new("10").js_gt(&Any::new(9)));
+        assert!(!Any::new(_undefined).js_le(&Any::new(_undefined)));
 
-#[cfg(test)]
-mod test_eq_for_any {
-    use super::*;
+        let nan = Any::new(f64::NAN);
+        assert!(!nan.js_lt(&nan) && !nan.js_le(&nan) && !nan.js_gt(&nan) && !nan.js_ge(&nan));
+    }
 
     #[test]
-    fn test_eq() {
-        let a = Any::new(5);
-        let b = Any::new(5);
-        assert_eq!(a, b);
+    fn test_partial_cmp_nan() {
+        let nan = Any::new(f64::NAN);
+        assert_eq!(nan.partial_cmp(&Any::new(1)), None);
+        // NaN is == to itself
+        assert_eq!(nan.partial_cmp(&nan), Some(Ordering::Equal));
+        assert!(!nan.lt(&Any::new(1)));
+        assert!(!nan.ge(&Any::new(1)));
 
-        let a = Any::new(5);
-        let b = Any::new(10);
-        assert_ne!(a, b);
+        let text = Any::new("abc");
+        assert_eq!(text.partial_cmp(&Any::new(1)), None);
+        assert!(!text.gt(&Any::new(1)));
+        assert!(!text.le(&Any::new(1)));
+    }
 
-        let a = Any::new(5);
-        let b = Any::new(5.0);
-        assert_ne!(a, b);
+    #[test]
+    fn test_cmp_total() {
+        assert_eq!(Any::new(1).cmp_total(&Any::new(1.0)), Ordering::Equal);
+        assert_eq!(Any::new(1).cmp_total(&Any::new(2.5)), Ordering::Less);
+        assert_eq!(
+            Any::new(f64::NAN).cmp_total(&Any::new(f64::NAN)),
+            Ordering::Equal
+        );
+        assert_eq!(
+            Any::new(f64::NAN).cmp_total(&Any::new(f64::INFINITY)),
+            Ordering::Greater
+        );
+        assert_eq!(Any::new("10").cmp_total(&Any::new(9)), Ordering::Greater);
+        assert_eq!(Any::new(true).cmp_total(&Any::new(0)), Ordering::Less);
+        assert_eq!(Any::new(_null).cmp_total(&Any::new(false)), Ordering::Less);
+
+        let a = Any::from(vec![1, 2]);
+        let b = Any::from(vec![1, 3]);
+        let c = Any::from(vec![1, 2, 0]);
+        assert_eq!(a.cmp_total(&b), Ordering::Less);
+        assert_eq!(a.cmp_total(&c), Ordering::Less);
+        assert_eq!(c.cmp_total(&b), Ordering::Less);
+
+        let mut m1 = Map::new();
+        m1.set("a", 1);
+        let mut m2 = Map::new();
+        m2.set("a", 2);
+        assert_eq!(Any::from(m1).cmp_total(&Any::from(m2)), Ordering::Less);
 
-        let a = Any::new(5);
-        let b = Any::new(5.0);
-        assert_ne!(a, b);
+        use crate as anyrust;
+        let m1 = map! { "a" => 1, "b" => 2 };
+        let m2 = map! { "b" => 2, "a" => 1.0 };
+        assert_eq!(m1, m2);
+        assert_eq!(m1.cmp_total(&m2), Ordering::Equal);
+        assert_eq!(m1.cmp_total(&map! { "b" => 1, "a" => 2 }), Ordering::Less);
 
-        let a = Any::new(5);
-        let b = Any::new("5");
-        assert_ne!(a, b);
+        let p1 = Any::from(Pair::new(1, "b"));
+        let p2 = Any::from(Pair::new(1, "c"));
+        assert_eq!(p1.cmp_total(&p2), Ordering::Less);
 
-        let a = Any::new(5);
-        let b = Any::new("5");
-        assert_ne!(a, b);
+        let f1 = function!(x => { x });
+        let f2 = function!(x => { x });
+        assert_eq!(f1.cmp_total(&f1.clone()), Ordering::Equal);
+        assert_ne!(f1.cmp_total(&f2), Ordering::Equal);
+        assert_eq!(f1.cmp_total(&f2), f2.cmp_total(&f1).reverse());
+    }
 
-        let a = Any::new(5);
-        let b = Any::new(true);
-        assert_ne!(a, b);
+    #[test]
+    fn test_sort_total() {
+        let mut values = [
+            Any::from(vec![0]),
+            Any::new("b"),
+            Any::new(f64::NAN),
+            Any::new(3_u8),
+            Any::new(_null),
+            Any::new(-1.5),
+            Any::new(true),
+            Any::new("a"),
+        ];
+        values.sort_by(Any::cmp_total);
 
-        let a = Any::new(5);
-        let b = Any::new(false);
-        assert_ne!(a, b);
+        let expected = vec!["null", "true", "-1.5", "3", "NaN", "a", "b", "[0]"];
+        let actual = values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+    }
 
-        let a = Any::new(5);
-        let b = Any::from(vec![1, 2, 3]);
-        assert_ne!(a, b);
+    #[test]
+    fn test_total_ord_btree_map() {
+        let mut map = std::collections::BTreeMap::new();
+        map.insert(TotalOrd(Any::new(2)), "two");
+        map.insert(TotalOrd(Any::new("one")), "one");
+        map.insert(TotalOrd(Any::new(1.0)), "one");
+        map.insert(TotalOrd(Any::new(1_u8)), "uno");
 
-        let a = Any::new(5);
-        let b = Any::from(HashMap::new());
-        assert_ne!(a, b);
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get(&TotalOrd(Any::new(1))), Some(&"uno"));
 
-        let a = Any::new(5);
-        let b = Any::new(_null);
-        assert_ne!(a, b);
+        let keys = map.into_keys().map(|k| k.0).collect::<Vec<_>>();
+        assert_eq!(keys, vec![Any::new(1.0), Any::new(2), Any::new("one")]);
     }
 }
 
-impl Hash for Any {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.type_id.hash(state);
-        match self.type_id {
-            type_id if type_id == *I8 => self.data.to_integer().hash(state),
-            type_id if type_id == *I16 => self.data.to_integer().hash(state),
-            type_id if type_id == *I32 => self.data.to_integer().hash(state),
-            type_id if type_id == *I64 => self.data.to_integer().hash(state),
-            type_id if type_id == *ISIZE => self.data.to_integer().hash(state),
-            type_id if type_id == *U8 => self.data.to_integer().hash(state),
-            type_id if type_id == *U16 => self.data.to_integer().hash(state),
-            type_id if type_id == *U32 => self.data.to_integer().hash(state),
-            type_id if type_id == *U64 => self.data.to_integer().hash(state),
-            type_id if type_id == *USIZE => self.data.to_integer().hash(state),
-            type_id if type_id == *F32 => self.data.to_float().to_bits().hash(state),
-            type_id if type_id == *F64 => self.data.to_float().to_bits().hash(state),
-            type_id if type_id == *STRING => self.data.to_string().hash(state),
-            type_id if type_id == *STR => self.data.to_string().hash(state),
-            type_id if type_id == *BOOL => self.data.to_boolean().hash(state),
-            type_id if type_id == *ARRAY => self.data.to_array().0.hash(state),
-            type_id if type_id == *MAP => self.data.to_string().hash(state),
-            _ => self.data.to_string().hash(state),
+/// largest array index, like in JavaScript (an array holds at most 2^32 - 1 elements)
+pub const MAX_ARRAY_INDEX: usize = u32::MAX as usize - 1;
+
+/// largest number of elements that one assignment past the end of an array may add
+///
+/// Arrays are not sparse, so a far index would allocate every element up to it.
+pub const MAX_ARRAY_GROWTH: usize = 1 << 24;
+
+/// Converts an index key to an array index, like JavaScript does for array elements.
+///
+/// Non-negative integers, integral floats and strings of digits are array indices. Anything else is not.
+fn array_index(key: &Any) -> Option<usize> {
+    match &key.value {
+        Value::Int(int) => usize::try_from(int.to_i128()).ok(),
+        Value::Float(float) => {
+            let float = float.to_f64();
+            if float >= 0.0 && float.fract() == 0.0 && float < usize::MAX as f64 {
+                Some(float as usize)
+            } else {
+                None
+            }
         }
+        Value::Str(string) if string.bytes().all(|b| b.is_ascii_digit()) => string.parse().ok(),
+        _ => None,
     }
 }
 
@@ -3275,24 +5271,30 @@ where
 {
     type Output = Any;
 
+    /// Returns the element for the key, or undefined if there is none. (see [`Any::get_opt`])
+    ///
+    /// Indexing a string returns the char at the index as a string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the char of a string is not ASCII, since a reference to it cannot be returned without caching it forever.
+    /// Use [`Any::char_at`] or [`Any::get_opt`], which return an owned value, for text that may hold other chars.
     fn index(&self, index: T) -> &Self::Output {
         let key: Any = index.into();
 
-        if self.type_id == *ARRAY {
-            let array = self.data.to_array_ref();
-            let key = key.data.to_integer() as usize;
-            if key >= array.0.len() {
-                return &null;
-            }
-
-            &array.0[key]
-        } else if self.type_id == *MAP {
-            let map = self.data.to_map_ref();
-
-            map.0.get(&key).unwrap_or(&null)
-        } else {
-            &null
-        }
+        let element = match &self.value {
+            Value::Str(string) => array_index(&key)
+                .and_then(|key| string.chars().nth(key))
+                .map(|c| {
+                    string::ascii_char_value(c).unwrap_or_else(|| {
+                        panic!(
+                            "Cannot index a non-ASCII char '{c}', use char_at or get_opt instead"
+                        )
+                    })
+                }),
+            _ => self.element(&key),
+        };
+        element.unwrap_or(&undefined)
     }
 }
 
@@ -3300,38 +5302,45 @@ impl<T> IndexMut<T> for Any
 where
     T: Into<Any>,
 {
+    /// Returns the element for the key, creating it if needed.
+    ///
+    /// - An array grows to fit the index, and the gap is filled with undefined, like `arr[10] = x` in JavaScript.
+    /// - A map inserts undefined for a missing key.
+    /// - Null or undefined becomes an empty array if the key is an array index, or an empty map otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not an array index for an array, or if the value is not an array, a map, null or undefined.
+    /// Also panics with "Invalid array length" if the index is above [`MAX_ARRAY_INDEX`],
+    /// or if the array would grow by more than [`MAX_ARRAY_GROWTH`] elements at once.
     fn index_mut(&mut self, index: T) -> &mut Self::Output {
         let key: Any = index.into();
+        let index = array_index(&key);
 
-        if self.type_id == *ARRAY {
-            let array = self.data.to_array_mut();
-            let key = key.data.to_integer() as usize;
-            if key >= array.0.len() {
-                unsafe {
-                    let uninit: std::mem::MaybeUninit<Self::Output> =
-                        std::mem::MaybeUninit::uninit();
-                    let ptr = uninit.as_ptr() as *mut Self::Output;
-                    *ptr = null.clone();
-                    return &mut *ptr;
-                }
-            }
-
-            &mut array.0[key]
-        } else if self.type_id == *MAP {
-            let map = self.data.to_map_mut();
+        if self.is_nullish() {
+            self.value = match index {
+                Some(_) => Value::Array(Array::new()),
+                None => Value::Map(Box::default()),
+            };
+        }
 
-            if map.0.get(&key).is_none() {
-                map.0.insert(key.clone(), null.clone());
-            }
+        match &mut self.value {
+            Value::Array(array) => {
+                let Some(index) = index else {
+                    panic!("cannot index Array with {}: {}", key.type_name(), key);
+                };
+                if index > MAX_ARRAY_INDEX || index.saturating_sub(array.0.len()) > MAX_ARRAY_GROWTH
+                {
+                    panic!("Invalid array length: cannot assign to index {index}");
+                }
+                if index >= array.0.len() {
+                    array.0.resize(index + 1, undefined.clone());
+                }
 
-            map.0.get_mut(&key).unwrap()
-        } else {
-            unsafe {
-                let uninit: std::mem::MaybeUninit<Self::Output> = std::mem::MaybeUninit::uninit();
-                let ptr = uninit.as_ptr() as *mut Self::Output;
-                *ptr = null.clone();
-                &mut *ptr
+                &mut array.0[index]
             }
+            Value::Map(map) => map.0.entry(key).or_insert_with(|| undefined.clone()),
+            value => panic!("cannot assign to an index of {}", value.type_name()),
         }
     }
 }
@@ -3346,7 +5355,7 @@ mod test_indexer_for_any {
         assert_eq!(a[0], Any::new(1));
         assert_eq!(a[1], Any::new(2));
         assert_eq!(a[2], Any::new(3));
-        assert_eq!(a[3], Any::new(_null));
+        assert_eq!(a[3], Any::new(_undefined));
     }
 
     #[test]
@@ -3358,7 +5367,102 @@ mod test_indexer_for_any {
         assert_eq!(a[Any::from(1)], Any::new(1));
         assert_eq!(a[Any::from(2)], Any::new(2));
         assert_eq!(a[Any::from(3)], Any::new(3));
-        assert_eq!(a[Any::from(4)], Any::new(_null));
+        assert_eq!(a[Any::from(4)], Any::new(_undefined));
+    }
+
+    #[test]
+    fn test_array_index_keys() {
+        let a = Any::from(vec![1, 2, 3]);
+        assert_eq!(a[1.0], Any::new(2));
+        assert_eq!(a["2"], Any::new(3));
+        assert_eq!(a[-1], Any::new(_undefined));
+        assert_eq!(a[1.5], Any::new(_undefined));
+        assert_eq!(a["1a"], Any::new(_undefined));
+        assert_eq!(a[u64::MAX], Any::new(_undefined));
+    }
+
+    #[test]
+    fn test_array_indexer_assign() {
+        let mut a = Any::from(vec![1, 2, 3]);
+        a[0] = Any::new(10);
+        a[3] = Any::new(4);
+        assert_eq!(a, Any::from(vec![10, 2, 3, 4]));
+
+        a[6] = Any::new(7);
+        assert_eq!(a.length(), Any::new(7_usize));
+        assert_eq!(a[3], Any::new(4));
+        assert!(a[4].is_undefined());
+        assert!(a[5].is_undefined());
+        assert_eq!(a[6], Any::new(7));
+
+        a["1"] = Any::new("two");
+        assert_eq!(a[1], Any::new("two"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid array length")]
+    fn test_array_indexer_assign_max() {
+        let mut a = Any::from(vec![1, 2, 3]);
+        a[u64::MAX] = Any::new(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid array length")]
+    fn test_array_indexer_assign_large() {
+        let mut a = Any::from(vec![1, 2, 3]);
+        a[4_000_000_000_000_u64] = Any::new(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid array length")]
+    fn test_array_indexer_assign_growth() {
+        let mut a = Any::new(_null);
+        a[MAX_ARRAY_INDEX] = Any::new(1);
+    }
+
+    #[test]
+    fn test_null_indexer_assign() {
+        let mut a = Any::new(_null);
+        a[2] = Any::new("c");
+        assert_eq!(a.to_string(), "[undefined, undefined, c]");
+
+        let mut b = Any::new(_null);
+        b["key"] = Any::new("value");
+        assert!(b.is_map());
+        assert_eq!(b["key"], Any::new("value"));
+
+        let mut c = Any::from(HashMap::new());
+        c["outer"]["inner"] = Any::new(1);
+        assert_eq!(c["outer"]["inner"], Any::new(1));
+    }
+
+    #[test]
+    fn test_scalar_indexer_read() {
+        let a = Any::new(5);
+        assert!(a[0].is_undefined());
+        assert!(a["key"].is_undefined());
+        assert!(Any::new(_null)[0].is_undefined());
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot assign to an index of i32")]
+    fn test_scalar_indexer_assign() {
+        let mut a = Any::new(5);
+        a[0] = Any::new(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot index Array with i32: -1")]
+    fn test_array_indexer_assign_negative() {
+        let mut a = Any::from(vec![1, 2, 3]);
+        a[-1] = Any::new(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot index Array with String: key")]
+    fn test_array_indexer_assign_string() {
+        let mut a = Any::from(vec![1, 2, 3]);
+        a["key"] = Any::new(1);
     }
 }
 
@@ -3367,23 +5471,19 @@ impl IntoIterator for Any {
     type IntoIter = AnyIterator;
 
     fn into_iter(self) -> Self::IntoIter {
-        if self.type_id == *ARRAY {
-            let array = self.data.to_array();
-            Box::new(array.0.into_iter())
-        } else if self.type_id == *MAP {
-            let map = self.data.to_map();
-            Box::new(map.0.into_iter().map(|(k, v)| Any::from(Pair::new(k, v))))
-        } else if self.type_id == *STRING || self.type_id == *STR {
-            let iter = self
-                .data
-                .to_string()
-                .chars()
-                .map(Any::from)
-                .collect::<Vec<_>>()
-                .into_iter();
-            Box::new(iter)
-        } else {
-            panic!("Cannot iterate over non-iterable type");
+        match self.value {
+            Value::Array(array) => Box::new(array.0.into_iter()),
+            Value::Map(map) => Box::new(map.0.into_iter().map(|(k, v)| Any::from(Pair::new(k, v)))),
+            Value::Set(set) => Box::new(set.0.into_iter()),
+            Value::Str(string) => {
+                let iter = string
+                    .chars()
+                    .map(Any::from)
+                    .collect::<Vec<_>>()
+                    .into_iter();
+                Box::new(iter)
+            }
+            _ => panic!("Cannot iterate over non-iterable type"),
         }
     }
 }
@@ -3414,16 +5514,11 @@ impl Shr for Any {
     type Output = Any;
 
     fn shr(self, other: Self) -> Self {
-        if self.type_id == *NULL || other.type_id == *NULL {
-            Any::new(_null)
-        } else if self.type_id == *FUNCTION && other.type_id == *FUNCTION {
-            let a = self.to_function();
-            let b = other.to_function();
-            Any::from(a.composite(b))
-        } else {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a >> b)
+        match (self.value, other.value) {
+            (Value::Null, Value::Undefined) | (Value::Undefined, Value::Null) => Any::new(0_i64),
+            (Value::Null, _) | (_, Value::Null) => Any::new(_null),
+            (Value::Function(a), Value::Function(b)) => Any::from(a.composite(b)),
+            (a, b) => Any::new(Any { value: a }.to_integer() >> Any { value: b }.to_integer()),
         }
     }
 }
@@ -3466,12 +5561,10 @@ impl Shl for Any {
     type Output = Any;
 
     fn shl(self, other: Self) -> Self {
-        if self.type_id == *NULL || other.type_id == *NULL {
-            Any::new(_null)
-        } else {
-            let a = self.data.to_integer();
-            let b = other.data.to_integer();
-            Any::new(a << b)
+        match (&self.value, &other.value) {
+            (Value::Null, Value::Undefined) | (Value::Undefined, Value::Null) => Any::new(0_i64),
+            (Value::Null, _) | (_, Value::Null) => Any::new(_null),
+            _ => Any::new(self.to_integer() << other.to_integer()),
         }
     }
 }
@@ -3518,8 +5611,8 @@ mod test_shl_for_any {
  ```rust
 use anyrust::*;
 let mut arr = array![1, 2, 3, 4, 5];
-arr.push(4444);
-arr.push("foo");
+arr.push(4444).unwrap();
+arr.push("foo").unwrap();
 
 for e in arr {
     println!("{e}");
@@ -3530,6 +5623,7 @@ for e in arr {
 macro_rules! array {
     ($($x:expr),*) => {
         {
+            #[allow(unused_mut)]
             let mut temp_vec = Vec::new();
             $(
                 temp_vec.push(Any::from($x));
@@ -3572,6 +5666,7 @@ macro_rules! function {
     ($($arg:ident),* => $body:block) => {
         {
             // for args_count
+            #[allow(unused_mut)]
             let mut n = 0;
 
             $(
@@ -3581,10 +5676,12 @@ macro_rules! function {
             )*
 
             anyrust::Any::from(anyrust::Function::new(move |args| {
-                let mut arg_index = 0;
+                #[allow(unused_variables)]
+                let arg_index = 0;
                 $(
                     let $arg = args[arg_index].clone();
-                    arg_index += 1;
+                    #[allow(unused_variables)]
+                    let arg_index = arg_index + 1;
                 )*
                 $body
             }, n))
@@ -3634,12 +5731,41 @@ assert_eq!(result, Any::from(1));
 macro_rules! map {
     ($($key:expr => $value:expr),* $(,)?) => {
         {
-            let mut temp_map = std::collections::HashMap::new();
+            #[allow(unused_mut)]
+            let mut temp_map = anyrust::Map::new();
+            $(
+                temp_map.set($key, $value);
+            )*
+
+            anyrust::Any::from(temp_map)
+        }
+    };
+}
+
+/// Create a new set
+///
+/// This provides a shortcut to creating a Set object via macro expansion. Duplicate values are added once.
+/**
+```rust
+use anyrust::*;
+
+let set = set![1, 2, 2, "three"];
+
+assert!(set.has(2));
+assert_eq!(set.length(), any(3_usize));
+```
+*/
+#[macro_export]
+macro_rules! set {
+    ($($value:expr),* $(,)?) => {
+        {
+            #[allow(unused_mut)]
+            let mut temp_set = anyrust::Set::new();
             $(
-                temp_map.insert(anyrust::Any::from($key), anyrust::Any::from($value));
+                temp_set.add($value);
             )*
 
-            anyrust::Any::from(anyrust::Map::from(temp_map))
+            anyrust::Any::from(temp_set)
         }
     };
 }
@@ -19,10 +19,10 @@ fn main() {
 
     println!("Result: {}", result);
 
-    let m = map!{
+    let m = map! {
         "name" => "John",
         "age" => 20,
     };
-    
+
     println!("Map: {}", m);
 }
@@ -0,0 +1,253 @@
+//! operators between Any and Rust primitives
+//!
+//! Every operator converts the primitive with `Any::from` first, so `a + 3` is the same as `a + any(3)`.
+//!
+//! `String + Any` and `String += Any` are not implemented: a second `Add` impl on `String`
+//! would break type inference for `s + &t` in code that does not use Any at all.
+//! Use `any(s) + a` instead.
+
+use std::{
+    cmp::Ordering,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign},
+};
+
+use crate::{Any, Decimal};
+
+/// Implements an arithmetic operator in both operand orders, and its assignment form on Any.
+macro_rules! impl_mixed_arithmetic {
+    ($t:ty, $($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident);*) => {
+        $(
+            impl $trait<$t> for Any {
+                type Output = Any;
+
+                fn $method(self, other: $t) -> Any {
+                    $trait::$method(self, Any::from(other))
+                }
+            }
+
+            impl $trait<Any> for $t {
+                type Output = Any;
+
+                fn $method(self, other: Any) -> Any {
+                    $trait::$method(Any::from(self), other)
+                }
+            }
+
+            impl $assign_trait<$t> for Any {
+                fn $assign_method(&mut self, other: $t) {
+                    $assign_trait::$assign_method(self, Any::from(other));
+                }
+            }
+        )*
+    };
+}
+
+/// Implements `==` and `<`, `>`, ... in both operand orders.
+macro_rules! impl_mixed_comparison {
+    ($t:ty, |$other:ident| $convert:expr) => {
+        impl PartialEq<$t> for Any {
+            fn eq(&self, $other: &$t) -> bool {
+                *self == $convert
+            }
+        }
+
+        impl PartialEq<Any> for $t {
+            fn eq(&self, other: &Any) -> bool {
+                let $other = self;
+                $convert == *other
+            }
+        }
+
+        impl PartialOrd<$t> for Any {
+            fn partial_cmp(&self, $other: &$t) -> Option<Ordering> {
+                self.partial_cmp(&$convert)
+            }
+        }
+
+        impl PartialOrd<Any> for $t {
+            fn partial_cmp(&self, other: &Any) -> Option<Ordering> {
+                let $other = self;
+                $convert.partial_cmp(other)
+            }
+        }
+    };
+}
+
+macro_rules! impl_mixed_operators {
+    ($($t:ty),*) => {
+        $(
+            impl_mixed_arithmetic!(
+                $t,
+                Add, add, AddAssign, add_assign;
+                Sub, sub, SubAssign, sub_assign;
+                Mul, mul, MulAssign, mul_assign;
+                Div, div, DivAssign, div_assign;
+                Rem, rem, RemAssign, rem_assign
+            );
+            impl_mixed_comparison!($t, |other| Any::from(*other));
+        )*
+    };
+}
+
+impl_mixed_operators!(
+    i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64, bool, char, Decimal
+);
+
+impl_mixed_arithmetic!(
+    &str,
+    Add, add, AddAssign, add_assign;
+    Sub, sub, SubAssign, sub_assign;
+    Mul, mul, MulAssign, mul_assign;
+    Div, div, DivAssign, div_assign;
+    Rem, rem, RemAssign, rem_assign
+);
+impl_mixed_comparison!(&str, |other| Any::from(*other));
+impl_mixed_comparison!(str, |other| Any::from(other));
+
+impl Add<String> for Any {
+    type Output = Any;
+
+    fn add(self, other: String) -> Any {
+        self + Any::from(other)
+    }
+}
+
+impl AddAssign<String> for Any {
+    fn add_assign(&mut self, other: String) {
+        *self += Any::from(other);
+    }
+}
+
+impl_mixed_comparison!(String, |other| Any::from(other.as_str()));
+
+#[cfg(test)]
+mod test_mixed_ops {
+    use super::*;
+    use crate::any;
+
+    #[test]
+    fn test_arithmetic() {
+        struct TestCase {
+            name: String,
+            result: Any,
+            expected: Any,
+        }
+
+        let a = any(10);
+        let test_cases = vec![
+            TestCase {
+                name: "any + i32".to_string(),
+                result: a.clone() + 3,
+                expected: a.clone() + any(3),
+            },
+            TestCase {
+                name: "i32 + any".to_string(),
+                result: 3 + a.clone(),
+                expected: any(3) + a.clone(),
+            },
+            TestCase {
+                name: "u8 - any".to_string(),
+                result: 3_u8 - a.clone(),
+                expected: any(3_u8) - a.clone(),
+            },
+            TestCase {
+                name: "any * f64".to_string(),
+                result: a.clone() * 1.5,
+                expected: any(15.0),
+            },
+            TestCase {
+                name: "f32 / any".to_string(),
+                result: 5.0_f32 / a.clone(),
+                expected: any(0.5),
+            },
+            TestCase {
+                name: "any % usize".to_string(),
+                result: a.clone() % 4_usize,
+                expected: any(2_i64),
+            },
+            TestCase {
+                name: "decimal * any".to_string(),
+                result: Decimal::new(15, 1) * a.clone(),
+                expected: Any::from(Decimal::new(150, 1)),
+            },
+            TestCase {
+                name: "any + bool".to_string(),
+                result: a.clone() + true,
+                expected: a.clone() + any(true),
+            },
+            TestCase {
+                name: "any + &str".to_string(),
+                result: a.clone() + "px",
+                expected: any("10px"),
+            },
+            TestCase {
+                name: "&str + any".to_string(),
+                result: "#" + a.clone(),
+                expected: any("#10"),
+            },
+            TestCase {
+                name: "any + String".to_string(),
+                result: a.clone() + String::from("px"),
+                expected: any("10px"),
+            },
+            TestCase {
+                name: "char + any".to_string(),
+                result: 'x' + a.clone(),
+                expected: any("x10"),
+            },
+        ];
+
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.result, test_case.expected,
+                "TC: {}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_assign() {
+        let mut a = any(10);
+        a += 5;
+        assert_eq!(a, any(15));
+
+        a -= 1_u8;
+        a *= 2;
+        a /= 4.0;
+        assert_eq!(a, any(7.0));
+
+        a %= 4;
+        assert_eq!(a, any(3.0));
+
+        let mut s = any("foo");
+        s += "bar";
+        s += String::from("!");
+        assert_eq!(s, "foobar!");
+    }
+
+    #[test]
+    fn test_comparison() {
+        let a = any(5);
+        assert!(a == 5);
+        assert!(5 == a);
+        assert!(a != 6);
+        assert!(a == 5_i64);
+        assert!(a.lt(&6.5));
+        assert!(6.5.gt(&a));
+        assert!(a.ge(&"4"));
+        assert!(a.partial_cmp(&"5").is_none());
+        assert!(a.lt(&true) == any(5).lt(&any(true)));
+
+        let s = any("foo");
+        assert!(s == "foo");
+        assert!("foo" == s);
+        assert!(s == *"foo");
+        let foo = String::from("foo");
+        assert!(s == foo);
+        assert!(foo == s);
+        assert!(s.lt(&"goo"));
+        assert!(any('a') == 'a');
+        assert!(any(true) == true);
+    }
+}
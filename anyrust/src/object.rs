@@ -0,0 +1,187 @@
+//! opaque user values stored in Any
+
+use std::fmt::{Debug, Display};
+
+use crate::{
+    Any, Array, Map, ToArray, ToBoolean, ToFloat, ToFunction, ToInteger, ToMap, ToPair, ToStr,
+};
+
+/// Wraps a user type so that it can be stored in Any without implementing the cast traits.
+///
+/// The value behaves like a plain JavaScript object: it is truthy, converts to NaN as a number
+/// and to an empty array or map. It is displayed with its `Debug` output.
+/// Like a JavaScript object, it is equal only to itself and its clones, whatever the value of `T`.
+/// Use [`Any::object`] to create one, and [`Any::downcast_ref`] to get the value back.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct Object<T>(pub T);
+
+impl<T: Debug> Debug for Object<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: Debug> Display for Object<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl<T> ToInteger for Object<T> {
+    fn to_integer(&self) -> i64 {
+        0
+    }
+}
+
+impl<T> ToFloat for Object<T> {
+    fn to_float(&self) -> f64 {
+        f64::NAN
+    }
+}
+
+impl<T: Debug> ToStr for Object<T> {
+    fn to_str(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl<T> ToArray for Object<T> {
+    fn to_array(&self) -> Array {
+        Array::new()
+    }
+}
+
+impl<T> ToMap for Object<T> {
+    fn to_map(&self) -> Map {
+        Map::new()
+    }
+}
+
+impl<T> ToBoolean for Object<T> {
+    fn to_boolean(&self) -> bool {
+        true
+    }
+}
+
+impl<T> ToPair for Object<T> {}
+
+impl<T> ToFunction for Object<T> {}
+
+impl Any {
+    /// Stores a user type as an opaque object.
+    ///
+    /// Unlike [`Any::new`], the type only needs to be `Clone + Debug` (and `Send + Sync`, like every value in Any).
+    /// ```
+    /// use anyrust::*;
+    ///
+    /// #[derive(Debug, Clone, PartialEq)]
+    /// struct Point { x: i32, y: i32 }
+    ///
+    /// let mut value = Any::object(Point { x: 1, y: 2 });
+    /// assert!(value.is_object());
+    /// assert_eq!(value.to_string(), "Point { x: 1, y: 2 }");
+    ///
+    /// value.downcast_mut::<Point>().unwrap().x = 10;
+    /// assert_eq!(value.downcast_ref::<Point>(), Some(&Point { x: 10, y: 2 }));
+    /// assert_eq!(value.downcast::<Point>().unwrap(), Point { x: 10, y: 2 });
+    /// ```
+    pub fn object<T>(value: T) -> Self
+    where
+        T: Clone + Debug + Send + Sync + 'static,
+    {
+        Any::new(Object(value))
+    }
+}
+
+#[cfg(test)]
+mod test_object {
+    use super::*;
+    use crate::{_null, CastError, Null};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[test]
+    fn test_object() {
+        let a = Any::object(Point { x: 1, y: 2 });
+        assert!(a.is_object());
+        assert!(a.to_boolean());
+        assert!(a.to_float().is_nan());
+        assert_eq!(a.to_str(), "Point { x: 1, y: 2 }");
+        assert_eq!(a.try_to_integer(), Err(CastError::new("object", "integer")));
+    }
+
+    #[test]
+    fn test_identity() {
+        use std::collections::HashSet;
+
+        let a = Any::object(Point { x: 1, y: 2 });
+        let b = a.clone();
+        assert_eq!(a, b);
+        assert_ne!(a, Any::object(Point { x: 1, y: 2 }));
+
+        let set: HashSet<Any> = [a.clone(), b.clone(), Any::object(Point { x: 1, y: 2 })].into();
+        assert_eq!(set.len(), 2);
+        assert_ne!(
+            a.cmp_total(&Any::object(Point { x: 1, y: 2 })),
+            std::cmp::Ordering::Equal
+        );
+
+        // a mutated clone is copied, and no longer the same object
+        let mut c = a.clone();
+        c.downcast_mut::<Point>().unwrap().x = 5;
+        assert_ne!(a, c);
+        assert_eq!(a.downcast_ref::<Point>(), Some(&Point { x: 1, y: 2 }));
+        assert_eq!(c.downcast_ref::<Point>(), Some(&Point { x: 5, y: 2 }));
+
+        // a failed downcast does not copy
+        let mut d = a.clone();
+        assert!(d.downcast_mut::<String>().is_none());
+        assert_eq!(a, d);
+    }
+
+    #[test]
+    fn test_downcast_ref() {
+        let a = Any::object(Point { x: 1, y: 2 });
+        assert_eq!(a.downcast_ref::<Point>(), Some(&Point { x: 1, y: 2 }));
+        assert_eq!(a.downcast_ref::<i32>(), None);
+
+        assert_eq!(Any::from(5_u8).downcast_ref::<u8>(), Some(&5));
+        assert_eq!(Any::from(5_u8).downcast_ref::<i32>(), None);
+        assert_eq!(
+            Any::from("foo").downcast_ref::<String>(),
+            Some(&"foo".to_string())
+        );
+        assert!(Any::from(_null).downcast_ref::<Null>().is_some());
+    }
+
+    #[test]
+    fn test_downcast_mut() {
+        let mut a = Any::object(Point { x: 1, y: 2 });
+        a.downcast_mut::<Point>().unwrap().y = 5;
+        assert_eq!(a.downcast_ref::<Point>(), Some(&Point { x: 1, y: 5 }));
+        assert!(a.downcast_mut::<String>().is_none());
+
+        let mut a = Any::from(1.5);
+        *a.downcast_mut::<f64>().unwrap() += 1.0;
+        assert_eq!(a, Any::from(2.5));
+
+        let mut a = Any::from(Array::new());
+        a.downcast_mut::<Array>().unwrap().push(1);
+        assert_eq!(a.to_string(), "[1]");
+    }
+
+    #[test]
+    fn test_downcast() {
+        let a = Any::object(Point { x: 1, y: 2 });
+        let a = a.downcast::<String>().unwrap_err();
+        assert_eq!(a.downcast::<Point>(), Ok(Point { x: 1, y: 2 }));
+
+        assert_eq!(Any::from("foo").downcast::<String>(), Ok("foo".to_string()));
+        assert_eq!(Any::from(3_i64).downcast::<i64>(), Ok(3));
+        assert_eq!(Any::from(3_i64).downcast::<i32>(), Err(Any::from(3_i64)));
+    }
+}
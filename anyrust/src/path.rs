@@ -0,0 +1,282 @@
+//! optional chaining and nullish coalescing, like JavaScript `?.` and `??`
+
+use std::{borrow::Cow, iter::Peekable, str::Chars};
+
+use crate::{_undefined, array_index, value::Value, Any};
+
+/// Implements optional chaining.
+impl Any {
+    /// Returns the element for the key, or None if there is none.
+    ///
+    /// Works like indexing: arrays take an index, maps take a key, and a string gives the char at the index.
+    /// Elements of arrays and maps are borrowed, while the char of a string is a new value.
+    /// ```
+    /// use anyrust::*;
+    ///
+    /// let value = map! { "a" => array![1, 2] };
+    /// assert_eq!(value.get_opt("a").as_deref(), Some(&array![1, 2]));
+    /// assert_eq!(value.path("a[1]"), any(2));
+    /// assert_eq!(any("ab").get_opt(1).as_deref(), Some(&any("b")));
+    /// assert_eq!(value.get_opt("b"), None);
+    /// assert_eq!(any(5).get_opt(0), None);
+    /// ```
+    pub fn get_opt(&self, key: impl Into<Any>) -> Option<Cow<'_, Any>> {
+        let key: Any = key.into();
+
+        match &self.value {
+            Value::Str(string) => array_index(&key)
+                .and_then(|key| string.chars().nth(key))
+                .map(|c| Cow::Owned(Any::from(c.to_string()))),
+            _ => self.element(&key).map(Cow::Borrowed),
+        }
+    }
+
+    /// Returns a reference to the element of an array or map for the key.
+    pub(crate) fn element(&self, key: &Any) -> Option<&Any> {
+        match &self.value {
+            Value::Array(array) => array_index(key).and_then(|key| array.0.get(key)),
+            Value::Map(map) => map.0.get(key),
+            _ => None,
+        }
+    }
+
+    /// Returns the value at the path, like `value?.a?.b?.[0]` in JavaScript.
+    ///
+    /// Keys are separated by `.`, and `[0]` or `["key"]` can be used for indices and keys with special characters.
+    /// Returns undefined if any link is missing, or if the path is malformed. A null at the end of the path is returned as is.
+    /// ```
+    /// use anyrust::*;
+    ///
+    /// let value = Any::parse_json(r#"{"a": {"b": [{"c": 1}, null]}, "x.y": 2}"#).unwrap();
+    /// assert_eq!(value.path("a.b[0].c"), any(1));
+    /// assert_eq!(value.path(r#"["x.y"]"#), any(2));
+    /// assert!(value.path("a.b[1]").is_null());
+    /// assert!(value.path("a.b[1].c").is_undefined());
+    /// assert!(value.path("a.missing.c").is_undefined());
+    /// ```
+    pub fn path(&self, path: &str) -> Any {
+        parse_path(path)
+            .and_then(|keys| {
+                keys.iter()
+                    .try_fold(Cow::Borrowed(self), |value, key| match value {
+                        Cow::Borrowed(value) => lookup(value, key),
+                        // only the char of a string is owned, and indexing it again gives the same char
+                        Cow::Owned(value) => {
+                            lookup(&value, key).map(|value| Cow::Owned(value.into_owned()))
+                        }
+                    })
+            })
+            .map(Cow::into_owned)
+            .unwrap_or_else(|| Any::from(_undefined))
+    }
+
+    /// Returns the default if the value is null or undefined, like `??` in JavaScript.
+    ///
+    /// Unlike `||`, other falsy values such as `0`, `""` and `false` are kept.
+    /// ```
+    /// use anyrust::*;
+    ///
+    /// let value = map! { "count" => 0, "name" => null.clone() };
+    /// assert_eq!(value.path("count").or_else_nullish(10), any(0));
+    /// assert_eq!(value.path("name").or_else_nullish("anonymous"), any("anonymous"));
+    /// assert_eq!(value.path("missing").or_else_nullish(10), any(10));
+    /// ```
+    pub fn or_else_nullish(&self, default: impl Into<Any>) -> Any {
+        if self.is_nullish() {
+            default.into()
+        } else {
+            self.clone()
+        }
+    }
+}
+
+/// Looks up one key of a path.
+///
+/// A path has no types, so a map is also searched for the key as an integer if it is not found as a string.
+fn lookup<'a>(value: &'a Any, key: &str) -> Option<Cow<'a, Any>> {
+    value
+        .get_opt(key)
+        .or_else(|| match (&value.value, key.parse::<i64>()) {
+            (Value::Map(_), Ok(index)) => value.get_opt(index),
+            _ => None,
+        })
+}
+
+/// Splits a path like `a.b[0]["c.d"]` into its keys. Returns None if the path is malformed.
+fn parse_path(path: &str) -> Option<Vec<String>> {
+    let mut keys = Vec::new();
+    let mut chars = path.chars().peekable();
+
+    while chars.peek().is_some() {
+        if chars.next_if_eq(&'[').is_some() {
+            keys.push(parse_bracket(&mut chars)?);
+            continue;
+        }
+
+        // every name after the first one follows a dot
+        if !keys.is_empty() {
+            chars.next_if_eq(&'.')?;
+        }
+
+        let mut name = String::new();
+        while let Some(c) = chars.next_if(|&c| c != '.' && c != '[') {
+            name.push(c);
+        }
+        if name.is_empty() {
+            return None;
+        }
+        keys.push(name);
+    }
+
+    Some(keys)
+}
+
+/// Parses the rest of `[0]`, `["key"]` or `['key']` after the opening bracket.
+fn parse_bracket(chars: &mut Peekable<Chars>) -> Option<String> {
+    let mut key = String::new();
+
+    match chars.next_if(|&c| c == '"' || c == '\'') {
+        Some(quote) => loop {
+            match chars.next()? {
+                c if c == quote => break,
+                '\\' => key.push(chars.next()?),
+                c => key.push(c),
+            }
+        },
+        None => {
+            while let Some(c) = chars.next_if(|&c| c != ']') {
+                key.push(c);
+            }
+            if key.is_empty() {
+                return None;
+            }
+        }
+    }
+
+    chars.next_if_eq(&']')?;
+    Some(key)
+}
+
+#[cfg(test)]
+mod test_path {
+    use super::*;
+    use crate as anyrust;
+    use crate::{_null, any, array, map};
+
+    fn value() -> Any {
+        map! {
+            "user" => map! {
+                "name" => "John",
+                "tags" => array!["a", "b"],
+                "email" => _null,
+            },
+            "a.b" => 1,
+            0 => "zero",
+        }
+    }
+
+    #[test]
+    fn test_path() {
+        struct TestCase {
+            path: &'static str,
+            result: Any,
+        }
+
+        let test_cases = vec![
+            TestCase {
+                path: "user.name",
+                result: any("John"),
+            },
+            TestCase {
+                path: "user.tags[1]",
+                result: any("b"),
+            },
+            TestCase {
+                path: "user.tags.0",
+                result: any("a"),
+            },
+            TestCase {
+                path: "user['tags'][0]",
+                result: any("a"),
+            },
+            TestCase {
+                path: "user.name[0]",
+                result: any("J"),
+            },
+            TestCase {
+                path: r#"["a.b"]"#,
+                result: any(1),
+            },
+            TestCase {
+                path: "[0]",
+                result: any("zero"),
+            },
+            TestCase {
+                path: "user.email",
+                result: any(_null),
+            },
+            TestCase {
+                path: "",
+                result: value(),
+            },
+        ];
+
+        for test_case in test_cases {
+            assert_eq!(
+                value().path(test_case.path),
+                test_case.result,
+                "TC: {}",
+                test_case.path
+            );
+        }
+    }
+
+    #[test]
+    fn test_broken_path() {
+        let paths = [
+            "missing",
+            "user.missing.name",
+            "user.email.name",
+            "user.tags[2]",
+            "user.tags[-1]",
+            "user.name.length",
+            "a.b",
+            "user.",
+            ".user",
+            "user..name",
+            "user[",
+            "user[]",
+            "user['name",
+            "user['name'",
+            "user['name']x",
+        ];
+
+        for path in paths {
+            assert!(value().path(path).is_undefined(), "TC: {path}");
+        }
+    }
+
+    #[test]
+    fn test_get_opt() {
+        let value = value();
+        assert_eq!(value.get_opt("a.b").as_deref(), Some(&any(1)));
+        assert_eq!(value.get_opt(0).as_deref(), Some(&any("zero")));
+        assert_eq!(value.get_opt("missing"), None);
+        assert!(matches!(value.get_opt("user"), Some(Cow::Borrowed(_))));
+        assert_eq!(array![1, 2].get_opt("1").as_deref(), Some(&any(2)));
+        assert_eq!(array![1, 2].get_opt(2), None);
+        assert!(matches!(any("ab").get_opt(1), Some(Cow::Owned(b)) if b == any("b")));
+        assert_eq!(any("한글").get_opt(1).as_deref(), Some(&any("글")));
+        assert_eq!(any(_null).get_opt(0), None);
+    }
+
+    #[test]
+    fn test_or_else_nullish() {
+        assert_eq!(any(_null).or_else_nullish(1), any(1));
+        assert_eq!(Any::from(_undefined).or_else_nullish(1), any(1));
+        assert_eq!(any(0).or_else_nullish(1), any(0));
+        assert_eq!(any("").or_else_nullish(1), any(""));
+        assert_eq!(any(false).or_else_nullish(1), any(false));
+        assert!(any(f64::NAN).or_else_nullish(1).is_nan());
+    }
+}
@@ -0,0 +1,427 @@
+//! serde support (enabled with the `serde` feature)
+
+use std::fmt;
+
+use indexmap::IndexMap;
+use serde::{
+    de::{self, MapAccess, SeqAccess, Visitor},
+    ser::{SerializeMap, SerializeSeq, SerializeTuple},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{
+    _null,
+    value::{Int, Value},
+    Any, Array, Map, Null, Pair, Set,
+};
+
+impl Serialize for Any {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match &self.value {
+            Value::Null | Value::Undefined | Value::Function(_) => serializer.serialize_unit(),
+            Value::Bool(boolean) => serializer.serialize_bool(*boolean),
+            // to_integer() would wrap values above i64::MAX
+            Value::Int(Int::U64(value)) => serializer.serialize_u64(*value),
+            Value::Int(Int::Usize(value)) => serializer.serialize_u64(*value as u64),
+            Value::Int(int) => serializer.serialize_i64(int.to_i64()),
+            Value::Float(float) => serializer.serialize_f64(float.to_f64()),
+            // a string keeps the exact digits and the scale, which an f64 would lose
+            Value::Decimal(decimal) => serializer.serialize_str(&decimal.to_string()),
+            // most formats cannot hold a larger integer, so it falls back to the decimal digits
+            Value::BigInt(bigint) => match i128::try_from(&**bigint) {
+                Ok(value) => serializer.serialize_i128(value),
+                Err(_) => serializer.serialize_str(&bigint.to_string()),
+            },
+            Value::Str(string) => serializer.serialize_str(string),
+            Value::Array(array) => array.serialize(serializer),
+            Value::Map(map) => map.serialize(serializer),
+            Value::Set(set) => set.serialize(serializer),
+            Value::Pair(pair) => pair.serialize(serializer),
+            Value::Custom(_) => serializer.serialize_str(&self.to_str()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Any {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(AnyVisitor)
+    }
+}
+
+struct AnyVisitor;
+
+impl<'de> Visitor<'de> for AnyVisitor {
+    type Value = Any;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any value")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Any, E>
+    where
+        E: de::Error,
+    {
+        Ok(Any::from(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Any, E>
+    where
+        E: de::Error,
+    {
+        Ok(Any::from(value))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Any, E>
+    where
+        E: de::Error,
+    {
+        // only values that do not fit in i64 are kept as u64
+        match i64::try_from(value) {
+            Ok(value) => Ok(Any::from(value)),
+            Err(_) => Ok(Any::from(value)),
+        }
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Any, E>
+    where
+        E: de::Error,
+    {
+        Ok(Any::from(value))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Any, E>
+    where
+        E: de::Error,
+    {
+        Ok(Any::from(value))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Any, E>
+    where
+        E: de::Error,
+    {
+        Ok(Any::from(value))
+    }
+
+    fn visit_unit<E>(self) -> Result<Any, E>
+    where
+        E: de::Error,
+    {
+        Ok(Any::from(_null))
+    }
+
+    fn visit_none<E>(self) -> Result<Any, E>
+    where
+        E: de::Error,
+    {
+        Ok(Any::from(_null))
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Any, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Any::deserialize(deserializer)
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Any, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        ArrayVisitor.visit_seq(seq).map(Any::from)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Any, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        MapVisitor.visit_map(map).map(Any::from)
+    }
+}
+
+impl Serialize for Array {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for value in &self.0 {
+            seq.serialize_element(value)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Array {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(ArrayVisitor)
+    }
+}
+
+impl Serialize for Set {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.size()))?;
+        for value in self.iter() {
+            seq.serialize_element(value)?;
+        }
+        seq.end()
+    }
+}
+
+/// Duplicate values in the sequence are added once.
+impl<'de> Deserialize<'de> for Set {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Array::deserialize(deserializer).map(|array| array.into_iter().collect())
+    }
+}
+
+struct ArrayVisitor;
+
+impl<'de> Visitor<'de> for ArrayVisitor {
+    type Value = Array;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Array, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+
+        Ok(Array(values))
+    }
+}
+
+impl Serialize for Map {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // left out like in to_json, so that both give the same JSON
+        let entries = self
+            .0
+            .iter()
+            .filter(|(_, value)| !value.is_function() && !value.is_undefined());
+
+        let mut map = serializer.serialize_map(Some(entries.clone().count()))?;
+        for (key, value) in entries {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Map {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(MapVisitor)
+    }
+}
+
+struct MapVisitor;
+
+impl<'de> Visitor<'de> for MapVisitor {
+    type Value = Map;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map")
+    }
+
+    fn visit_map<A>(self, mut access: A) -> Result<Map, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut map = IndexMap::with_capacity(access.size_hint().unwrap_or(0));
+        while let Some((key, value)) = access.next_entry()? {
+            map.insert(key, value);
+        }
+
+        Ok(Map(map))
+    }
+}
+
+impl Serialize for Pair {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&self.0 .0)?;
+        tuple.serialize_element(&self.0 .1)?;
+        tuple.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Pair {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        <(Any, Any)>::deserialize(deserializer).map(Pair)
+    }
+}
+
+impl Serialize for Null {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_unit()
+    }
+}
+
+impl<'de> Deserialize<'de> for Null {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_unit(NullVisitor)
+    }
+}
+
+struct NullVisitor;
+
+impl<'de> Visitor<'de> for NullVisitor {
+    type Value = Null;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("null")
+    }
+
+    fn visit_unit<E>(self) -> Result<Null, E>
+    where
+        E: de::Error,
+    {
+        Ok(_null)
+    }
+
+    fn visit_none<E>(self) -> Result<Null, E>
+    where
+        E: de::Error,
+    {
+        Ok(_null)
+    }
+}
+
+#[cfg(test)]
+mod test_serde {
+    use super::*;
+    use crate as anyrust;
+    use crate::{any, array, function, map, pair};
+
+    #[test]
+    fn test_serialize_primitives() {
+        assert_eq!(serde_json::to_string(&Any::from(5)).unwrap(), "5");
+        assert_eq!(
+            serde_json::to_string(&Any::from(u64::MAX)).unwrap(),
+            u64::MAX.to_string()
+        );
+        assert_eq!(
+            serde_json::to_string(&Any::from(i128::MIN)).unwrap(),
+            i128::MIN.to_string()
+        );
+        assert_eq!(
+            serde_json::to_string(&Any::from(crate::BigInt::from(u128::MAX) * 2)).unwrap(),
+            format!("\"{}\"", crate::BigInt::from(u128::MAX) * 2)
+        );
+        assert_eq!(serde_json::to_string(&Any::from(1.5)).unwrap(), "1.5");
+        assert_eq!(
+            serde_json::to_string(&Any::parse_decimal("0.10").unwrap()).unwrap(),
+            "\"0.10\""
+        );
+        assert_eq!(serde_json::to_string(&Any::from(true)).unwrap(), "true");
+        assert_eq!(serde_json::to_string(&Any::from("foo")).unwrap(), "\"foo\"");
+        assert_eq!(serde_json::to_string(&Any::from(_null)).unwrap(), "null");
+        assert_eq!(serde_json::to_string(&_null).unwrap(), "null");
+    }
+
+    #[test]
+    fn test_serialize_collections() {
+        let a = array![1, "two", 3.5, _null];
+        assert_eq!(serde_json::to_string(&a).unwrap(), r#"[1,"two",3.5,null]"#);
+
+        let m = map! { "name" => "John" };
+        assert_eq!(serde_json::to_string(&m).unwrap(), r#"{"name":"John"}"#);
+
+        let p = pair!(1, "one");
+        assert_eq!(serde_json::to_string(&p).unwrap(), r#"[1,"one"]"#);
+
+        let f = function!(x => { x });
+        assert_eq!(serde_json::to_string(&f).unwrap(), "null");
+
+        let m = map! { "a" => 1, "b" => crate::_undefined, "c" => f, "d" => _null };
+        assert_eq!(serde_json::to_string(&m).unwrap(), r#"{"a":1,"d":null}"#);
+        assert_eq!(serde_json::to_string(&m).unwrap(), m.to_json());
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let value: Any = serde_json::from_str(
+            r#"{"name": "John", "age": 20, "height": 180.5, "tags": ["a", "b"], "spouse": null, "adult": true}"#,
+        )
+        .unwrap();
+
+        assert!(value.is_map());
+        assert_eq!(value.get("name"), Any::from("John"));
+        assert_eq!(value.get("age"), Any::from(20_i64));
+        assert_eq!(value.get("height"), Any::from(180.5));
+        assert_eq!(value.get("tags"), array!["a", "b"]);
+        assert!(value.get("spouse").is_null());
+        assert_eq!(value.get("adult"), Any::from(true));
+
+        let big: Any = serde_json::from_str(&u64::MAX.to_string()).unwrap();
+        assert_eq!(big, Any::from(u64::MAX));
+    }
+
+    #[test]
+    fn test_deserialize_concrete_types() {
+        let a: Array = serde_json::from_str("[1, 2, 3]").unwrap();
+        assert_eq!(a, Array::from(vec![any(1_i64), any(2_i64), any(3_i64)]));
+
+        let m: Map = serde_json::from_str(r#"{"foo": 1}"#).unwrap();
+        assert_eq!(m.get(&any("foo")), Some(&any(1_i64)));
+
+        let p: Pair = serde_json::from_str(r#"["key", 1]"#).unwrap();
+        assert_eq!(p.to_tuple(), (any("key"), any(1_i64)));
+
+        let s: Set = serde_json::from_str("[1, 2, 1]").unwrap();
+        assert_eq!(s, [1, 2].into_iter().collect());
+        assert_eq!(serde_json::to_string(&s).unwrap(), "[1,2]");
+
+        let _: Null = serde_json::from_str("null").unwrap();
+        assert!(serde_json::from_str::<Null>("1").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let value = array![1, "two", map! { "three" => 3.5 }, array![true, _null]];
+        let json = serde_json::to_string(&value).unwrap();
+        let parsed: Any = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            parsed,
+            array![1_i64, "two", map! { "three" => 3.5 }, array![true, _null]]
+        );
+    }
+}
@@ -0,0 +1,326 @@
+//! set type, like JavaScript `Set`
+
+use std::{fmt::Display, hash::Hash};
+
+use indexmap::IndexSet;
+
+use crate::{
+    value::Value, Any, Array, CastError, Map, ToArray, ToBoolean, ToFloat, ToFunction, ToInteger,
+    ToMap, ToPair, ToStr,
+};
+
+/// set of unique values
+///
+/// Values keep their insertion order, like a JavaScript `Set`.
+/// Values are unique by `==` on Any, so `1` and `1.0` are the same value and NaN can be added once.
+#[derive(Debug, Clone, Default)]
+pub struct Set(pub(crate) IndexSet<Any>);
+
+impl Set {
+    pub fn new() -> Self {
+        Self(IndexSet::new())
+    }
+
+    /// Adds the value. Returns false if it was already in the set, which keeps its position.
+    pub fn add(&mut self, value: impl Into<Any>) -> bool {
+        self.0.insert(value.into())
+    }
+
+    /// Returns true if the set contains the value.
+    pub fn has(&self, value: impl Into<Any>) -> bool {
+        self.0.contains(&value.into())
+    }
+
+    /// Removes the value. Returns false if it was not in the set.
+    ///
+    /// The remaining values keep their order.
+    pub fn delete(&mut self, value: impl Into<Any>) -> bool {
+        self.0.shift_remove(&value.into())
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Returns the number of values, like `size` in JavaScript.
+    pub fn size(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterates over the values in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = &Any> {
+        self.0.iter()
+    }
+
+    /// Returns the values of both sets. The values of self come first.
+    pub fn union(&self, other: &Set) -> Set {
+        Set(self.0.union(&other.0).cloned().collect())
+    }
+
+    /// Returns the values of self that are also in the other set.
+    pub fn intersection(&self, other: &Set) -> Set {
+        Set(self.0.intersection(&other.0).cloned().collect())
+    }
+
+    /// Returns the values of self that are not in the other set.
+    pub fn difference(&self, other: &Set) -> Set {
+        Set(self.0.difference(&other.0).cloned().collect())
+    }
+
+    /// Returns the values that are in exactly one of the sets.
+    pub fn symmetric_difference(&self, other: &Set) -> Set {
+        Set(self.0.symmetric_difference(&other.0).cloned().collect())
+    }
+
+    /// Returns true if every value of self is in the other set.
+    pub fn is_subset_of(&self, other: &Set) -> bool {
+        self.0.is_subset(&other.0)
+    }
+}
+
+/// Sets are equal if they have the same values, in any order.
+impl PartialEq for Set {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for Set {}
+
+/// Combined like `Hash for Map`, so that the order of the values does not matter.
+impl Hash for Set {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let values = self.0.iter().fold(0_u64, |sum, value| {
+            let mut hasher = std::hash::DefaultHasher::new();
+            value.hash(&mut hasher);
+            sum.wrapping_add(std::hash::Hasher::finish(&hasher))
+        });
+
+        self.0.len().hash(state);
+        values.hash(state);
+    }
+}
+
+impl<T: Into<Any>> FromIterator<T> for Set {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Set(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+impl IntoIterator for Set {
+    type Item = Any;
+    type IntoIter = indexmap::set::IntoIter<Any>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl From<Set> for Any {
+    fn from(value: Set) -> Self {
+        Any::new(value)
+    }
+}
+
+impl Display for Set {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_str())
+    }
+}
+
+impl ToInteger for Set {
+    fn to_integer(&self) -> i64 {
+        0_i64
+    }
+}
+
+impl ToFloat for Set {
+    fn to_float(&self) -> f64 {
+        0 as f64
+    }
+}
+
+impl ToStr for Set {
+    fn to_str(&self) -> String {
+        let values = self.0.iter().map(Any::to_str).collect::<Vec<_>>();
+        format!("Set {{{}}}", values.join(", "))
+    }
+}
+
+/// Converts to the values in insertion order, like `Array.from(set)`.
+impl ToArray for Set {
+    fn to_array(&self) -> Array {
+        self.0.iter().cloned().collect::<Vec<_>>().into()
+    }
+}
+
+impl ToMap for Set {
+    fn to_map(&self) -> Map {
+        Map::new()
+    }
+}
+
+impl ToBoolean for Set {
+    fn to_boolean(&self) -> bool {
+        true
+    }
+}
+
+impl ToPair for Set {}
+
+impl ToFunction for Set {}
+
+/// Implements basic behavior for Set objects.
+impl Any {
+    /// Returns true if the type is a set.
+    pub fn is_set(&self) -> bool {
+        matches!(self.value, Value::Set(_))
+    }
+
+    /// Converts the type to a set.
+    ///
+    /// An array gives a set of its elements, like `new Set(array)`. Other values give an empty set.
+    pub fn to_set(&self) -> Set {
+        match &self.value {
+            Value::Set(set) => set.as_ref().clone(),
+            Value::Array(array) => array.0.iter().cloned().collect(),
+            _ => Set::new(),
+        }
+    }
+
+    /// Converts the type to a set. Succeeds for sets and arrays.
+    pub fn try_to_set(&self) -> Result<Set, CastError> {
+        match &self.value {
+            Value::Set(_) | Value::Array(_) => Ok(self.to_set()),
+            _ => Err(self.cast_error("Set")),
+        }
+    }
+
+    /// Adds a value to the set.
+    pub fn add(&mut self, value: impl Into<Any>) {
+        if let Value::Set(set) = &mut self.value {
+            set.add(value);
+        }
+    }
+
+    /// Returns true if the set contains the value, or the map contains the key.
+    pub fn has(&self, value: impl Into<Any>) -> bool {
+        match &self.value {
+            Value::Set(set) => set.has(value),
+            Value::Map(map) => map.get(&value.into()).is_some(),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_set {
+    use super::*;
+    use crate as anyrust;
+    use crate::{any, set};
+
+    fn numbers() -> Set {
+        [1, 2, 3].into_iter().collect()
+    }
+
+    #[test]
+    fn test_add_has_delete() {
+        let mut s = Set::new();
+        assert!(s.is_empty());
+
+        assert!(s.add(1));
+        assert!(s.add("one"));
+        assert!(!s.add(1.0));
+        assert!(s.add(f64::NAN));
+        assert!(!s.add(f64::NAN));
+        assert_eq!(s.size(), 3);
+
+        assert!(s.has(1_u8));
+        assert!(s.has(f64::NAN));
+        assert!(!s.has(2));
+
+        assert!(s.delete(1));
+        assert!(!s.delete(1));
+        assert_eq!(s.size(), 2);
+
+        s.clear();
+        assert!(s.is_empty());
+    }
+
+    #[test]
+    fn test_insertion_order() {
+        let mut s: Set = ["c", "a", "b"].into_iter().collect();
+        s.add("a");
+        assert_eq!(s.to_str(), "Set {c, a, b}");
+
+        s.delete("c");
+        s.add("c");
+        let values = s.into_iter().collect::<Vec<_>>();
+        assert_eq!(values, vec![any("a"), any("b"), any("c")]);
+    }
+
+    #[test]
+    fn test_algebra() {
+        let a = numbers();
+        let b: Set = [2, 3, 4].into_iter().collect();
+
+        assert_eq!(a.union(&b).to_str(), "Set {1, 2, 3, 4}");
+        assert_eq!(a.intersection(&b).to_str(), "Set {2, 3}");
+        assert_eq!(a.difference(&b).to_str(), "Set {1}");
+        assert_eq!(a.symmetric_difference(&b).to_str(), "Set {1, 4}");
+
+        assert!(a.intersection(&b).is_subset_of(&a));
+        assert!(!a.is_subset_of(&b));
+        assert!(Set::new().is_subset_of(&a));
+    }
+
+    #[test]
+    fn test_eq_and_hash() {
+        use std::collections::HashSet;
+
+        let a = numbers();
+        let b: Set = [3.0, 2.0, 1.0].into_iter().collect();
+        assert_eq!(a, b);
+
+        let set: HashSet<Any> = [Any::from(a), Any::from(b)].into();
+        assert_eq!(set.len(), 1);
+
+        let (a, b) = (set! { 1, 2 }, set! { 2, 1 });
+        assert_eq!(a, b);
+        assert_eq!(a.cmp_total(&b), std::cmp::Ordering::Equal);
+        assert_eq!(a.cmp_total(&set! { 3, 0 }), std::cmp::Ordering::Greater);
+    }
+
+    #[test]
+    fn test_cast() {
+        let a = Any::from(numbers());
+        assert!(a.is_set());
+        assert!(a.to_boolean());
+        assert_eq!(a.to_float(), 0.0);
+        assert_eq!(a.to_string(), "Set {1, 2, 3}");
+        assert_eq!(a.to_array().to_string(), "[1, 2, 3]");
+        assert!(a.to_map().is_empty());
+        assert_eq!(a.length(), any(3_usize));
+
+        let array = Any::from(Array::from(vec![any(1), any(1), any(2)]));
+        assert_eq!(array.to_set().size(), 2);
+        assert_eq!(array.try_to_set(), Ok([1, 2].into_iter().collect()));
+        assert_eq!(any(1).try_to_set(), Err(CastError::new("i32", "Set")));
+    }
+
+    #[test]
+    fn test_any_methods() {
+        let mut a = Any::from(Set::new());
+        a.add(1);
+        a.add(2);
+        a.add(1);
+        assert!(a.has(1));
+        assert_eq!(a.delete(1), any(1));
+        assert!(!a.has(1));
+        assert_eq!(a.into_iter().collect::<Vec<_>>(), vec![any(2)]);
+    }
+}
@@ -0,0 +1,459 @@
+//! JavaScript String methods (String.prototype)
+//!
+//! Indices and lengths are counted in chars (Unicode scalar values), not in UTF-16 code units as in JavaScript.
+
+use std::sync::OnceLock;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{value::Value, Any, Array};
+
+/// Returns an ASCII char as a string value that lives until the end of the program, or None for any other char.
+///
+/// `Index` has to return a reference, so the 128 ASCII chars are built once into a shared table.
+/// Other chars are not cached, so that indexing never grows memory or takes a lock.
+pub(crate) fn ascii_char_value(c: char) -> Option<&'static Any> {
+    static ASCII: OnceLock<[Any; 128]> = OnceLock::new();
+
+    c.is_ascii().then(|| {
+        let ascii =
+            ASCII.get_or_init(|| std::array::from_fn(|i| Any::from((i as u8 as char).to_string())));
+        &ascii[c as usize]
+    })
+}
+
+/// Returns the byte offset of the char at the index, or the length of the string if the index is past the end.
+fn byte_offset(string: &str, index: usize) -> usize {
+    string
+        .char_indices()
+        .nth(index)
+        .map_or(string.len(), |(offset, _)| offset)
+}
+
+/// Returns the chars in `start..end`, or an empty string if `start >= end`.
+fn char_range(string: &str, start: usize, end: usize) -> &str {
+    if start >= end {
+        return "";
+    }
+
+    let start_offset = byte_offset(string, start);
+    let end_offset = start_offset + byte_offset(&string[start_offset..], end - start);
+    &string[start_offset..end_offset]
+}
+
+/// Resolves a relative index like `String.prototype.slice`.
+///
+/// A negative index counts back from the end, and the result is clamped to `0..=length`.
+fn relative_index(index: isize, length: usize) -> usize {
+    if index < 0 {
+        length.saturating_sub(index.unsigned_abs())
+    } else {
+        (index as usize).min(length)
+    }
+}
+
+pub(crate) fn includes(string: &str, search: &str) -> bool {
+    string.contains(search)
+}
+
+/// Returns the char index of the first occurrence of the search string.
+pub(crate) fn index_of(string: &str, search: &str) -> Option<usize> {
+    let offset = string.find(search)?;
+    Some(string[..offset].chars().count())
+}
+
+pub(crate) fn slice(string: &str, start: isize, end: Option<isize>) -> String {
+    let length = string.chars().count();
+    let start = relative_index(start, length);
+    let end = end.map_or(length, |end| relative_index(end, length));
+    char_range(string, start, end).to_string()
+}
+
+/// Builds the padding for `pad_start` and `pad_end`.
+fn padding(length: usize, target_length: usize, pad: &str) -> String {
+    if target_length <= length || pad.is_empty() {
+        return String::new();
+    }
+
+    pad.chars().cycle().take(target_length - length).collect()
+}
+
+/// Implements JavaScript String methods for Any.
+///
+/// Any other value is converted with `to_str()` first.
+impl Any {
+    pub(crate) fn with_str<R>(&self, f: impl FnOnce(&str) -> R) -> R {
+        match &self.value {
+            Value::Str(string) => f(string),
+            _ => f(&self.to_str()),
+        }
+    }
+
+    /// Splits the string by the separator and returns an array of strings.
+    ///
+    /// An empty separator splits the string into chars.
+    /**
+    ```
+    use anyrust::*;
+
+    assert_eq!(any("a,b,c").split(","), array!["a", "b", "c"]);
+    assert_eq!(any("abc").split(""), array!["a", "b", "c"]);
+    ```
+    */
+    pub fn split(&self, separator: &str) -> Any {
+        self.with_str(|string| {
+            let values: Vec<Any> = if separator.is_empty() {
+                string.chars().map(Any::from).collect()
+            } else {
+                string.split(separator).map(Any::from).collect()
+            };
+            Any::from(Array::from(values))
+        })
+    }
+
+    /// Removes whitespace from both ends of the string.
+    pub fn trim(&self) -> Any {
+        self.with_str(|string| Any::from(string.trim()))
+    }
+
+    /// Removes whitespace from the beginning of the string.
+    pub fn trim_start(&self) -> Any {
+        self.with_str(|string| Any::from(string.trim_start()))
+    }
+
+    /// Removes whitespace from the end of the string.
+    pub fn trim_end(&self) -> Any {
+        self.with_str(|string| Any::from(string.trim_end()))
+    }
+
+    /// Converts the string to upper case.
+    pub fn to_upper_case(&self) -> Any {
+        self.with_str(|string| Any::from(string.to_uppercase()))
+    }
+
+    /// Converts the string to lower case.
+    pub fn to_lower_case(&self) -> Any {
+        self.with_str(|string| Any::from(string.to_lowercase()))
+    }
+
+    /// Returns true if the string starts with the search string.
+    pub fn starts_with(&self, search: &str) -> bool {
+        self.with_str(|string| string.starts_with(search))
+    }
+
+    /// Returns true if the string ends with the search string.
+    pub fn ends_with(&self, search: &str) -> bool {
+        self.with_str(|string| string.ends_with(search))
+    }
+
+    /// Returns the part of the string between the two indices.
+    ///
+    /// Negative indices are treated as 0, and the indices are swapped if `start` is greater than `end`.
+    /// A `None` end means the end of the string.
+    /**
+    ```
+    use anyrust::*;
+
+    assert_eq!(any("hello").substring(1, 3), any("el"));
+    assert_eq!(any("hello").substring(3, 1), any("el"));
+    assert_eq!(any("hello").substring(-2, None), any("hello"));
+    ```
+    */
+    pub fn substring(&self, start: isize, end: impl Into<Option<isize>>) -> Any {
+        let end = end.into();
+        self.with_str(|string| {
+            let length = string.chars().count();
+            let clamp = |index: isize| (index.max(0) as usize).min(length);
+            let start = clamp(start);
+            let end = end.map_or(length, clamp);
+            let (start, end) = (start.min(end), start.max(end));
+            Any::from(char_range(string, start, end))
+        })
+    }
+
+    /// Replaces the first occurrence of the pattern.
+    pub fn replace(&self, pattern: &str, replacement: &str) -> Any {
+        self.with_str(|string| Any::from(string.replacen(pattern, replacement, 1)))
+    }
+
+    /// Replaces every occurrence of the pattern.
+    pub fn replace_all(&self, pattern: &str, replacement: &str) -> Any {
+        self.with_str(|string| Any::from(string.replace(pattern, replacement)))
+    }
+
+    /// Pads the beginning of the string with the pad string until it is `target_length` chars long.
+    /**
+    ```
+    use anyrust::*;
+
+    assert_eq!(any(5).pad_start(3, "0"), any("005"));
+    assert_eq!(any("abc").pad_start(8, "12"), any("12121abc"));
+    ```
+    */
+    pub fn pad_start(&self, target_length: usize, pad: &str) -> Any {
+        self.with_str(|string| {
+            let mut result = padding(string.chars().count(), target_length, pad);
+            result.push_str(string);
+            Any::from(result)
+        })
+    }
+
+    /// Pads the end of the string with the pad string until it is `target_length` chars long.
+    pub fn pad_end(&self, target_length: usize, pad: &str) -> Any {
+        self.with_str(|string| {
+            let padding = padding(string.chars().count(), target_length, pad);
+            Any::from(string.to_string() + &padding)
+        })
+    }
+
+    /// Returns the string repeated `count` times.
+    pub fn repeat(&self, count: usize) -> Any {
+        self.with_str(|string| Any::from(string.repeat(count)))
+    }
+
+    /// Returns the char at the index as a string, or an empty string if the index is out of range.
+    pub fn char_at(&self, index: usize) -> Any {
+        self.with_str(|string| {
+            let c = string.chars().nth(index);
+            Any::from(c.map(String::from).unwrap_or_default())
+        })
+    }
+
+    /// Returns the length of the string in UTF-16 code units, like `length` in JavaScript.
+    /**
+    ```
+    use anyrust::*;
+
+    let s = any("한글😀");
+    assert_eq!(s.length(), any(3_usize));
+    assert_eq!(s.length_utf16(), any(4_usize));
+    ```
+    */
+    pub fn length_utf16(&self) -> Any {
+        self.with_str(|string| Any::from(string.encode_utf16().count()))
+    }
+
+    /// Returns the number of grapheme clusters (user-perceived characters) in the string.
+    /**
+    ```
+    use anyrust::*;
+
+    let family = any("👨‍👩‍👧");
+    assert_eq!(family.length(), any(5_usize));
+    assert_eq!(family.length_graphemes(), any(1_usize));
+    ```
+    */
+    pub fn length_graphemes(&self) -> Any {
+        self.with_str(|string| Any::from(string.graphemes(true).count()))
+    }
+
+    /// Splits the string into an array of grapheme clusters.
+    pub fn graphemes(&self) -> Any {
+        self.with_str(|string| {
+            let values: Vec<Any> = string.graphemes(true).map(Any::from).collect();
+            Any::from(Array::from(values))
+        })
+    }
+
+    /// Returns the grapheme cluster at the index, or undefined if the index is out of range.
+    pub fn grapheme_at(&self, index: usize) -> Any {
+        self.with_str(|string| match string.graphemes(true).nth(index) {
+            Some(grapheme) => Any::from(grapheme),
+            None => Any::from(crate::_undefined),
+        })
+    }
+
+    /// Returns the UTF-16 code unit at the UTF-16 index, like `charCodeAt` in JavaScript. Returns NaN if the index is out of range.
+    pub fn code_unit_at(&self, index: usize) -> Any {
+        self.with_str(|string| match string.encode_utf16().nth(index) {
+            Some(unit) => Any::from(unit as i64),
+            None => Any::from(f64::NAN),
+        })
+    }
+
+    /// Returns the code point of the char at the index, or NaN if the index is out of range.
+    ///
+    /// Unlike JavaScript, this never returns half of a surrogate pair, because indices are counted in chars.
+    pub fn char_code_at(&self, index: usize) -> Any {
+        self.with_str(|string| match string.chars().nth(index) {
+            Some(c) => Any::from(c as i64),
+            None => Any::from(f64::NAN),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_string_methods {
+    use super::*;
+    use crate as anyrust;
+    use crate::{_null, array};
+
+    #[test]
+    fn test_split() {
+        assert_eq!(Any::from("a,b,,c").split(","), array!["a", "b", "", "c"]);
+        assert_eq!(Any::from("a, b").split(", "), array!["a", "b"]);
+        assert_eq!(Any::from("abc").split("x"), array!["abc"]);
+        assert_eq!(Any::from("").split(","), array![""]);
+        assert_eq!(Any::from("한글").split(""), array!["한", "글"]);
+        assert_eq!(Any::from(1.5).split("."), array!["1", "5"]);
+    }
+
+    #[test]
+    fn test_trim() {
+        let s = Any::from("  hello \n");
+        assert_eq!(s.trim(), Any::from("hello"));
+        assert_eq!(s.trim_start(), Any::from("hello \n"));
+        assert_eq!(s.trim_end(), Any::from("  hello"));
+    }
+
+    #[test]
+    fn test_case() {
+        assert_eq!(Any::from("Hello").to_upper_case(), Any::from("HELLO"));
+        assert_eq!(Any::from("Hello").to_lower_case(), Any::from("hello"));
+        assert_eq!(Any::from("straße").to_upper_case(), Any::from("STRASSE"));
+        assert_eq!(Any::from(true).to_upper_case(), Any::from("TRUE"));
+    }
+
+    #[test]
+    fn test_starts_ends_with() {
+        let s = Any::from("hello world");
+        assert!(s.starts_with("hello"));
+        assert!(!s.starts_with("world"));
+        assert!(s.ends_with("world"));
+        assert!(!s.ends_with("hello"));
+        assert!(Any::from(123).starts_with("12"));
+    }
+
+    #[test]
+    fn test_includes_index_of() {
+        let s = Any::from("가나다 abc");
+        assert!(s.includes("다 a"));
+        assert!(!s.includes("xyz"));
+        assert_eq!(s.index_of("abc"), Any::from(4_i64));
+        assert_eq!(s.index_of("xyz"), Any::from(-1_i64));
+        assert_eq!(s.index_of(""), Any::from(0_i64));
+        assert_eq!(Any::from(12345).index_of(34), Any::from(2_i64));
+    }
+
+    #[test]
+    fn test_slice() {
+        let s = Any::from("héllo");
+        assert_eq!(s.slice(1, 3), Any::from("él"));
+        assert_eq!(s.slice(1, None), Any::from("éllo"));
+        assert_eq!(s.slice(-3, None), Any::from("llo"));
+        assert_eq!(s.slice(0, -1), Any::from("héll"));
+        assert_eq!(s.slice(3, 1), Any::from(""));
+        assert_eq!(s.slice(-10, 10), Any::from("héllo"));
+    }
+
+    #[test]
+    fn test_substring() {
+        let s = Any::from("héllo");
+        assert_eq!(s.substring(1, 3), Any::from("él"));
+        assert_eq!(s.substring(3, 1), Any::from("él"));
+        assert_eq!(s.substring(-1, 2), Any::from("hé"));
+        assert_eq!(s.substring(2, None), Any::from("llo"));
+        assert_eq!(s.substring(2, 100), Any::from("llo"));
+    }
+
+    #[test]
+    fn test_replace() {
+        let s = Any::from("a-b-c");
+        assert_eq!(s.replace("-", "+"), Any::from("a+b-c"));
+        assert_eq!(s.replace_all("-", "+"), Any::from("a+b+c"));
+        assert_eq!(s.replace("x", "+"), Any::from("a-b-c"));
+        assert_eq!(Any::from("abc").replace_all("", "-"), Any::from("-a-b-c-"));
+    }
+
+    #[test]
+    fn test_pad() {
+        assert_eq!(Any::from("5").pad_start(3, "0"), Any::from("005"));
+        assert_eq!(Any::from("abc").pad_start(6, "12"), Any::from("121abc"));
+        assert_eq!(Any::from("abc").pad_start(2, "0"), Any::from("abc"));
+        assert_eq!(Any::from("abc").pad_start(6, ""), Any::from("abc"));
+        assert_eq!(Any::from("abc").pad_end(6, "."), Any::from("abc..."));
+        assert_eq!(Any::from("한").pad_end(3, "글"), Any::from("한글글"));
+    }
+
+    #[test]
+    fn test_repeat() {
+        assert_eq!(Any::from("ab").repeat(3), Any::from("ababab"));
+        assert_eq!(Any::from("ab").repeat(0), Any::from(""));
+        assert_eq!(Any::from(_null).repeat(2), Any::from("nullnull"));
+    }
+
+    #[test]
+    fn test_length() {
+        let s = Any::from("한글");
+        assert_eq!(s.length(), Any::from(2_usize));
+        assert_eq!(s.length_utf16(), Any::from(2_usize));
+        assert_eq!(s.length_graphemes(), Any::from(2_usize));
+
+        let s = Any::from("a😀");
+        assert_eq!(s.length(), Any::from(2_usize));
+        assert_eq!(s.length_utf16(), Any::from(3_usize));
+        assert_eq!(s.length_graphemes(), Any::from(2_usize));
+
+        // 한 written as conjoining jamo, and a flag
+        let s = Any::from("\u{1112}\u{1161}\u{11AB}🇰🇷");
+        assert_eq!(s.length(), Any::from(5_usize));
+        assert_eq!(s.length_utf16(), Any::from(7_usize));
+        assert_eq!(s.length_graphemes(), Any::from(2_usize));
+
+        assert_eq!(Any::from("").length(), Any::from(0_usize));
+        assert_eq!(Any::from(12.5).length_utf16(), Any::from(4_usize));
+    }
+
+    #[test]
+    fn test_index() {
+        let s = Any::from("ab한😀");
+        assert_eq!(s[0], Any::from("a"));
+        assert_eq!(s["1"], Any::from("b"));
+        assert!(s[4].is_undefined());
+        assert!(s[-1].is_undefined());
+
+        // the same char is shared between strings
+        assert!(std::ptr::eq(&s[1], &Any::from("abc")[1]));
+
+        // other chars are owned values
+        assert_eq!(s.char_at(2), Any::from("한"));
+        assert_eq!(s.get_opt(3).as_deref(), Some(&Any::from("😀")));
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot index a non-ASCII char")]
+    fn test_index_non_ascii() {
+        let _ = &Any::from("a한")[1];
+    }
+
+    #[test]
+    fn test_graphemes() {
+        let s = Any::from("e\u{301}👨‍👩‍👧!");
+        assert_eq!(s.graphemes(), array!["e\u{301}", "👨‍👩‍👧", "!"]);
+        assert_eq!(s.grapheme_at(1), Any::from("👨‍👩‍👧"));
+        assert!(s.grapheme_at(3).is_undefined());
+    }
+
+    #[test]
+    fn test_code_unit_at() {
+        let s = Any::from("a😀");
+        assert_eq!(s.code_unit_at(0), Any::from(97_i64));
+        assert_eq!(s.code_unit_at(1), Any::from(0xD83D_i64));
+        assert_eq!(s.code_unit_at(2), Any::from(0xDE00_i64));
+        assert!(s.code_unit_at(3).is_nan());
+    }
+
+    #[test]
+    fn test_char_at() {
+        let s = Any::from("a한😀");
+        assert_eq!(s.char_at(0), Any::from("a"));
+        assert_eq!(s.char_at(1), Any::from("한"));
+        assert_eq!(s.char_at(2), Any::from("😀"));
+        assert_eq!(s.char_at(3), Any::from(""));
+
+        assert_eq!(s.char_code_at(0), Any::from(97_i64));
+        assert_eq!(s.char_code_at(1), Any::from(0xD55C_i64));
+        assert_eq!(s.char_code_at(2), Any::from(0x1F600_i64));
+        assert!(s.char_code_at(3).is_nan());
+    }
+}
@@ -0,0 +1,468 @@
+//! internal representation of Any
+
+use std::{any::TypeId, sync::Arc};
+
+use num_bigint::BigInt;
+use rust_decimal::Decimal;
+
+use crate::{_null, _undefined, Anyable, Array, Function, Map, Null, Pair, Set, Undefined};
+
+/// integer value that keeps the original width
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Int {
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    Isize(isize),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    Usize(usize),
+}
+
+impl Int {
+    /// Values above i64::MAX saturate.
+    pub(crate) fn to_i64(self) -> i64 {
+        match self {
+            Int::I8(v) => v as i64,
+            Int::I16(v) => v as i64,
+            Int::I32(v) => v as i64,
+            Int::I64(v) => v,
+            Int::Isize(v) => v as i64,
+            Int::U8(v) => v as i64,
+            Int::U16(v) => v as i64,
+            Int::U32(v) => v as i64,
+            Int::U64(v) => i64::try_from(v).unwrap_or(i64::MAX),
+            Int::Usize(v) => i64::try_from(v).unwrap_or(i64::MAX),
+        }
+    }
+
+    /// lossless value (every supported width fits in i128)
+    pub(crate) fn to_i128(self) -> i128 {
+        match self {
+            Int::I8(v) => v as i128,
+            Int::I16(v) => v as i128,
+            Int::I32(v) => v as i128,
+            Int::I64(v) => v as i128,
+            Int::Isize(v) => v as i128,
+            Int::U8(v) => v as i128,
+            Int::U16(v) => v as i128,
+            Int::U32(v) => v as i128,
+            Int::U64(v) => v as i128,
+            Int::Usize(v) => v as i128,
+        }
+    }
+
+    pub(crate) fn to_f64(self) -> f64 {
+        match self {
+            Int::I8(v) => v as f64,
+            Int::I16(v) => v as f64,
+            Int::I32(v) => v as f64,
+            Int::I64(v) => v as f64,
+            Int::Isize(v) => v as f64,
+            Int::U8(v) => v as f64,
+            Int::U16(v) => v as f64,
+            Int::U32(v) => v as f64,
+            Int::U64(v) => v as f64,
+            Int::Usize(v) => v as f64,
+        }
+    }
+
+    pub(crate) fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        match self {
+            Int::I8(v) => v,
+            Int::I16(v) => v,
+            Int::I32(v) => v,
+            Int::I64(v) => v,
+            Int::Isize(v) => v,
+            Int::U8(v) => v,
+            Int::U16(v) => v,
+            Int::U32(v) => v,
+            Int::U64(v) => v,
+            Int::Usize(v) => v,
+        }
+    }
+
+    pub(crate) fn into_boxed(self) -> Box<dyn std::any::Any> {
+        match self {
+            Int::I8(v) => Box::new(v),
+            Int::I16(v) => Box::new(v),
+            Int::I32(v) => Box::new(v),
+            Int::I64(v) => Box::new(v),
+            Int::Isize(v) => Box::new(v),
+            Int::U8(v) => Box::new(v),
+            Int::U16(v) => Box::new(v),
+            Int::U32(v) => Box::new(v),
+            Int::U64(v) => Box::new(v),
+            Int::Usize(v) => Box::new(v),
+        }
+    }
+
+    pub(crate) fn as_anyable(&self) -> &dyn Anyable {
+        match self {
+            Int::I8(v) => v,
+            Int::I16(v) => v,
+            Int::I32(v) => v,
+            Int::I64(v) => v,
+            Int::Isize(v) => v,
+            Int::U8(v) => v,
+            Int::U16(v) => v,
+            Int::U32(v) => v,
+            Int::U64(v) => v,
+            Int::Usize(v) => v,
+        }
+    }
+
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            Int::I8(_) => "i8",
+            Int::I16(_) => "i16",
+            Int::I32(_) => "i32",
+            Int::I64(_) => "i64",
+            Int::Isize(_) => "isize",
+            Int::U8(_) => "u8",
+            Int::U16(_) => "u16",
+            Int::U32(_) => "u32",
+            Int::U64(_) => "u64",
+            Int::Usize(_) => "usize",
+        }
+    }
+}
+
+/// Type promotion and overflow handling for integer arithmetic.
+///
+/// The result type of an operation between two integers follows this lattice:
+/// - the same type on both sides keeps that type
+/// - two signed (or two unsigned) types give the wider one. isize and usize rank as 64 bits,
+///   so isize with i64 gives i64 and usize with u64 gives u64
+/// - a signed and an unsigned type give the signed type if it is wider,
+///   otherwise the next signed type that holds the unsigned one (u8 → i16, u16 → i32, u32 → i64, u64 → i64)
+///
+/// The methods that take a value use the variant of self as the target type.
+impl Int {
+    fn signed_and_bits(self) -> (bool, u32) {
+        match self {
+            Int::I8(_) => (true, 8),
+            Int::I16(_) => (true, 16),
+            Int::I32(_) => (true, 32),
+            Int::I64(_) | Int::Isize(_) => (true, 64),
+            Int::U8(_) => (false, 8),
+            Int::U16(_) => (false, 16),
+            Int::U32(_) => (false, 32),
+            Int::U64(_) | Int::Usize(_) => (false, 64),
+        }
+    }
+
+    fn zero(signed: bool, bits: u32) -> Int {
+        match (signed, bits) {
+            (true, 8) => Int::I8(0),
+            (true, 16) => Int::I16(0),
+            (true, 32) => Int::I32(0),
+            (true, _) => Int::I64(0),
+            (false, 8) => Int::U8(0),
+            (false, 16) => Int::U16(0),
+            (false, 32) => Int::U32(0),
+            (false, _) => Int::U64(0),
+        }
+    }
+
+    /// Returns an integer of the result type of an operation between a and b. (the value is meaningless)
+    pub(crate) fn promote(a: Int, b: Int) -> Int {
+        if std::mem::discriminant(&a) == std::mem::discriminant(&b) {
+            return a;
+        }
+
+        let ((a_signed, a_bits), (b_signed, b_bits)) = (a.signed_and_bits(), b.signed_and_bits());
+
+        if a_signed == b_signed {
+            match a_bits.cmp(&b_bits) {
+                std::cmp::Ordering::Greater => a,
+                std::cmp::Ordering::Less => b,
+                std::cmp::Ordering::Equal => Int::zero(a_signed, a_bits),
+            }
+        } else {
+            let (signed, signed_bits, unsigned_bits) = if a_signed {
+                (a, a_bits, b_bits)
+            } else {
+                (b, b_bits, a_bits)
+            };
+
+            if signed_bits > unsigned_bits {
+                signed
+            } else {
+                Int::zero(true, unsigned_bits * 2)
+            }
+        }
+    }
+
+    fn bounds(self) -> (i128, i128) {
+        match self {
+            Int::I8(_) => (i8::MIN as i128, i8::MAX as i128),
+            Int::I16(_) => (i16::MIN as i128, i16::MAX as i128),
+            Int::I32(_) => (i32::MIN as i128, i32::MAX as i128),
+            Int::I64(_) => (i64::MIN as i128, i64::MAX as i128),
+            Int::Isize(_) => (isize::MIN as i128, isize::MAX as i128),
+            Int::U8(_) => (0, u8::MAX as i128),
+            Int::U16(_) => (0, u16::MAX as i128),
+            Int::U32(_) => (0, u32::MAX as i128),
+            Int::U64(_) => (0, u64::MAX as i128),
+            Int::Usize(_) => (0, usize::MAX as i128),
+        }
+    }
+
+    /// Stores the value in the type of self. Returns None if it does not fit.
+    pub(crate) fn checked_with(self, value: i128) -> Option<Int> {
+        let (min, max) = self.bounds();
+
+        if value < min || value > max {
+            None
+        } else {
+            Some(self.wrapping_with(value))
+        }
+    }
+
+    /// Stores the value in the type of self, wrapping around at the boundary of the type.
+    pub(crate) fn wrapping_with(self, value: i128) -> Int {
+        match self {
+            Int::I8(_) => Int::I8(value as i8),
+            Int::I16(_) => Int::I16(value as i16),
+            Int::I32(_) => Int::I32(value as i32),
+            Int::I64(_) => Int::I64(value as i64),
+            Int::Isize(_) => Int::Isize(value as isize),
+            Int::U8(_) => Int::U8(value as u8),
+            Int::U16(_) => Int::U16(value as u16),
+            Int::U32(_) => Int::U32(value as u32),
+            Int::U64(_) => Int::U64(value as u64),
+            Int::Usize(_) => Int::Usize(value as usize),
+        }
+    }
+
+    /// Stores the value in the type of self, clamped to the bounds of the type.
+    pub(crate) fn saturating_with(self, value: i128) -> Int {
+        let (min, max) = self.bounds();
+        self.wrapping_with(value.clamp(min, max))
+    }
+}
+
+/// float value that keeps the original width
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Float {
+    F32(f32),
+    F64(f64),
+}
+
+impl Float {
+    pub(crate) fn to_f64(self) -> f64 {
+        match self {
+            Float::F32(v) => v as f64,
+            Float::F64(v) => v,
+        }
+    }
+
+    pub(crate) fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        match self {
+            Float::F32(v) => v,
+            Float::F64(v) => v,
+        }
+    }
+
+    pub(crate) fn into_boxed(self) -> Box<dyn std::any::Any> {
+        match self {
+            Float::F32(v) => Box::new(v),
+            Float::F64(v) => Box::new(v),
+        }
+    }
+
+    pub(crate) fn as_anyable(&self) -> &dyn Anyable {
+        match self {
+            Float::F32(v) => v,
+            Float::F64(v) => v,
+        }
+    }
+
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            Float::F32(_) => "f32",
+            Float::F64(_) => "f64",
+        }
+    }
+}
+
+/// tagged value stored in Any
+///
+/// Built-in types are stored inline so that operators can dispatch with a single match.
+/// Any other Anyable type is stored in `Custom`, shared between clones so that it compares by identity like a JavaScript object.
+#[derive(Debug, Clone)]
+pub(crate) enum Value {
+    Int(Int),
+    Float(Float),
+    BigInt(Box<BigInt>),
+    Decimal(Decimal),
+    Str(String),
+    Bool(bool),
+    Array(Array),
+    Map(Box<Map>),
+    Set(Box<Set>),
+    Pair(Box<Pair>),
+    Function(Function),
+    Null,
+    Undefined,
+    Custom(Arc<dyn Anyable>),
+}
+
+impl Value {
+    /// Converts an Anyable value to the matching variant.
+    ///
+    /// The type checks are resolved at compile time, so this costs nothing for built-in types.
+    pub(crate) fn new<T: Anyable>(value: T) -> Self {
+        let mut slot = Some(value);
+
+        if let Some(v) = take::<T, i8>(&mut slot) {
+            Value::Int(Int::I8(v))
+        } else if let Some(v) = take::<T, i16>(&mut slot) {
+            Value::Int(Int::I16(v))
+        } else if let Some(v) = take::<T, i32>(&mut slot) {
+            Value::Int(Int::I32(v))
+        } else if let Some(v) = take::<T, i64>(&mut slot) {
+            Value::Int(Int::I64(v))
+        } else if let Some(v) = take::<T, isize>(&mut slot) {
+            Value::Int(Int::Isize(v))
+        } else if let Some(v) = take::<T, u8>(&mut slot) {
+            Value::Int(Int::U8(v))
+        } else if let Some(v) = take::<T, u16>(&mut slot) {
+            Value::Int(Int::U16(v))
+        } else if let Some(v) = take::<T, u32>(&mut slot) {
+            Value::Int(Int::U32(v))
+        } else if let Some(v) = take::<T, u64>(&mut slot) {
+            Value::Int(Int::U64(v))
+        } else if let Some(v) = take::<T, usize>(&mut slot) {
+            Value::Int(Int::Usize(v))
+        } else if let Some(v) = take::<T, f32>(&mut slot) {
+            Value::Float(Float::F32(v))
+        } else if let Some(v) = take::<T, f64>(&mut slot) {
+            Value::Float(Float::F64(v))
+        } else if let Some(v) = take::<T, String>(&mut slot) {
+            Value::Str(v)
+        } else if let Some(v) = take::<T, &'static str>(&mut slot) {
+            Value::Str(v.to_string())
+        } else if let Some(v) = take::<T, BigInt>(&mut slot) {
+            Value::BigInt(Box::new(v))
+        } else if let Some(v) = take::<T, Decimal>(&mut slot) {
+            Value::Decimal(v)
+        } else if let Some(v) = take::<T, bool>(&mut slot) {
+            Value::Bool(v)
+        } else if let Some(v) = take::<T, Array>(&mut slot) {
+            Value::Array(v)
+        } else if let Some(v) = take::<T, Map>(&mut slot) {
+            Value::Map(Box::new(v))
+        } else if let Some(v) = take::<T, Set>(&mut slot) {
+            Value::Set(Box::new(v))
+        } else if let Some(v) = take::<T, Pair>(&mut slot) {
+            Value::Pair(Box::new(v))
+        } else if let Some(v) = take::<T, Function>(&mut slot) {
+            Value::Function(v)
+        } else if take::<T, Null>(&mut slot).is_some() {
+            Value::Null
+        } else if take::<T, Undefined>(&mut slot).is_some() {
+            Value::Undefined
+        } else {
+            match slot {
+                Some(value) => Value::Custom(Arc::new(value)),
+                None => unreachable!(),
+            }
+        }
+    }
+
+    /// Returns the stored value as a trait object, for the conversions that are not on a hot path.
+    pub(crate) fn as_anyable(&self) -> &dyn Anyable {
+        match self {
+            Value::Int(v) => v.as_anyable(),
+            Value::Float(v) => v.as_anyable(),
+            Value::BigInt(v) => &**v,
+            Value::Decimal(v) => v,
+            Value::Str(v) => v,
+            Value::Bool(v) => v,
+            Value::Array(v) => v,
+            Value::Map(v) => &**v,
+            Value::Set(v) => &**v,
+            Value::Pair(v) => &**v,
+            Value::Function(v) => v,
+            Value::Null => &_null,
+            Value::Undefined => &_undefined,
+            Value::Custom(v) => &**v,
+        }
+    }
+
+    pub(crate) fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        match self {
+            Value::Int(v) => v.as_any_mut(),
+            Value::Float(v) => v.as_any_mut(),
+            Value::BigInt(v) => &mut **v,
+            Value::Decimal(v) => v,
+            Value::Str(v) => v,
+            Value::Bool(v) => v,
+            Value::Array(v) => v,
+            Value::Map(v) => &mut **v,
+            Value::Set(v) => &mut **v,
+            Value::Pair(v) => &mut **v,
+            Value::Function(v) => v,
+            // Null and Undefined are zero-sized, so this does not allocate
+            Value::Null => Box::leak(Box::new(_null)),
+            Value::Undefined => Box::leak(Box::new(_undefined)),
+            // copied on write, so the clones that share it are not changed
+            Value::Custom(v) => dyn_clone::arc_make_mut::<dyn Anyable>(v),
+        }
+    }
+
+    /// Moves the stored value out as a boxed std::any::Any.
+    pub(crate) fn into_boxed(self) -> Box<dyn std::any::Any> {
+        match self {
+            Value::Int(v) => v.into_boxed(),
+            Value::Float(v) => v.into_boxed(),
+            Value::BigInt(v) => v,
+            Value::Decimal(v) => Box::new(v),
+            Value::Str(v) => Box::new(v),
+            Value::Bool(v) => Box::new(v),
+            Value::Array(v) => Box::new(v),
+            Value::Map(v) => v,
+            Value::Set(v) => v,
+            Value::Pair(v) => v,
+            Value::Function(v) => Box::new(v),
+            Value::Null => Box::new(_null),
+            Value::Undefined => Box::new(_undefined),
+            Value::Custom(v) => dyn_clone::clone_box::<dyn Anyable>(&*v),
+        }
+    }
+
+    pub(crate) fn type_id(&self) -> TypeId {
+        let value: &dyn std::any::Any = self.as_anyable();
+        value.type_id()
+    }
+
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            Value::Int(v) => v.type_name(),
+            Value::Float(v) => v.type_name(),
+            Value::BigInt(_) => "BigInt",
+            Value::Decimal(_) => "Decimal",
+            Value::Str(_) => "String",
+            Value::Bool(_) => "bool",
+            Value::Array(_) => "Array",
+            Value::Map(_) => "Map",
+            Value::Set(_) => "Set",
+            Value::Pair(_) => "Pair",
+            Value::Function(_) => "Function",
+            Value::Null => "Null",
+            Value::Undefined => "Undefined",
+            Value::Custom(_) => "object",
+        }
+    }
+}
+
+/// Moves the value out of the slot if T is U.
+fn take<T: 'static, U: 'static>(slot: &mut Option<T>) -> Option<U> {
+    let slot: &mut dyn std::any::Any = slot;
+    slot.downcast_mut::<Option<U>>()?.take()
+}
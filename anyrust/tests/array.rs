@@ -0,0 +1,85 @@
+use anyrust::*;
+
+#[test]
+fn test_push_pop() {
+    let mut a = array![1, 2, 3, 4, 5];
+    a.push(4444).unwrap();
+    a.push("foo").unwrap();
+    assert_eq!(a, array![1, 2, 3, 4, 5, 4444, "foo"]);
+
+    assert_eq!(a.pop(), Some(any("foo")));
+    assert_eq!(a.pop(), Some(any(4444)));
+    assert_eq!(a.length(), any(5_usize));
+}
+
+#[test]
+fn test_shift_unshift() {
+    let mut a = array![2, 3];
+    a.unshift(1).unwrap();
+    assert_eq!(a, array![1, 2, 3]);
+
+    assert_eq!(a.shift(), Some(any(1)));
+    assert_eq!(a.shift(), Some(any(2)));
+    assert_eq!(a.shift(), Some(any(3)));
+    assert_eq!(a.shift(), None);
+    assert_eq!(a, array![]);
+}
+
+#[test]
+fn test_reverse() {
+    let mut a = array![1, "two", 3.0];
+    assert_eq!(a.reverse(), array![3.0, "two", 1]);
+    assert_eq!(a, array![3.0, "two", 1]);
+}
+
+#[test]
+fn test_null_is_promoted_to_array() {
+    let mut a = null.clone();
+    a.push(1).unwrap();
+    assert_eq!(a, array![1]);
+
+    let mut b = null.clone();
+    b.unshift("first").unwrap();
+    assert_eq!(b, array!["first"]);
+
+    let mut c = undefined.clone();
+    c.push(1).unwrap();
+    assert_eq!(c, array![1]);
+}
+
+#[test]
+fn test_non_array_is_left_unchanged() {
+    let mut a = any("foo");
+    assert_eq!(a.push("bar"), Err(CastError::new("String", "Array")));
+    assert_eq!(a, any("foo"));
+
+    let mut b = any(2);
+    assert_eq!(b.unshift(1), Err(CastError::new("i32", "Array")));
+    assert_eq!(b, any(2));
+
+    let mut c = map! { "key" => "value" };
+    assert_eq!(c.push(1), Err(CastError::new("Map", "Array")));
+    assert_eq!(c, map! { "key" => "value" });
+
+    let mut a = any(5);
+    assert_eq!(a.pop(), None);
+    assert_eq!(a.shift(), None);
+    assert_eq!(a.reverse(), any(5));
+    assert_eq!(a, any(5));
+
+    let mut b = null.clone();
+    assert_eq!(b.pop(), None);
+    assert!(b.is_null());
+}
+
+#[test]
+fn test_mutate_nested_array() {
+    let mut a = map! { "list" => array![1] };
+    a["list"].push(2).unwrap();
+    assert_eq!(a["list"], array![1, 2]);
+
+    let mut b = array![array![], array![1]];
+    b[0].push("x").unwrap();
+    b[1].unshift(0).unwrap();
+    assert_eq!(b, array![array!["x"], array![0, 1]]);
+}
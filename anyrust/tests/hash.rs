@@ -0,0 +1,169 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::OnceLock,
+};
+
+use anyrust::*;
+use proptest::{
+    collection::vec,
+    prelude::{prop, prop_assert, prop_assert_eq, prop_oneof, proptest, Just, Strategy},
+};
+
+fn hash_of(value: &Any) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// functions are compared by identity, so they are drawn from a fixed pool
+fn functions() -> &'static [Any] {
+    static FUNCTIONS: OnceLock<Vec<Any>> = OnceLock::new();
+    FUNCTIONS.get_or_init(|| vec![function!(x => { x }), function!(x => { x })])
+}
+
+/// small numbers of every type, so that equal values of different types are generated often
+fn number() -> impl Strategy<Value = Any> {
+    prop_oneof![
+        (0_i8..4).prop_map(any),
+        (0_u8..4).prop_map(any),
+        (0_i64..4).prop_map(any),
+        (0_u64..4).prop_map(any),
+        (0_usize..4).prop_map(any),
+        (0_u8..4).prop_map(|n| any(n as f64)),
+        (0_u8..4).prop_map(|n| any(n as f32 + 0.5)),
+        (0_u8..4).prop_map(|n| any(n as f64 + 0.5)),
+        (0_i64..40).prop_map(|n| Any::from(Decimal::new(n, 1))),
+        (0_i128..4).prop_map(Any::from),
+        Just(any(f64::NAN)),
+        Just(any(f32::NAN)),
+        Just(any(-0.0)),
+    ]
+}
+
+fn leaf() -> impl Strategy<Value = Any> {
+    prop_oneof![
+        number(),
+        "[ab]{0,2}".prop_map(any),
+        prop::bool::ANY.prop_map(any),
+        Just(null.clone()),
+        Just(undefined.clone()),
+        (0..functions().len()).prop_map(|i| functions()[i].clone()),
+    ]
+}
+
+fn value() -> impl Strategy<Value = Any> {
+    leaf().prop_recursive(3, 24, 4, |inner| {
+        prop_oneof![
+            vec(inner.clone(), 0..4).prop_map(|values| Any::from(Array::from(values))),
+            vec((inner.clone(), inner.clone()), 0..4)
+                .prop_map(|entries| Any::from(entries.into_iter().collect::<Map>())),
+            vec(inner.clone(), 0..4)
+                .prop_map(|values| Any::from(values.into_iter().collect::<Set>())),
+            (inner.clone(), inner).prop_map(|(key, value)| Any::from(Pair::new(key, value))),
+        ]
+    })
+}
+
+/// rebuilds every map and set with its entries in reverse order
+fn reverse_maps(value: &Any) -> Any {
+    if value.is_map() {
+        let mut entries = value.to_map().into_iter().collect::<Vec<_>>();
+        entries.reverse();
+        let map = entries
+            .into_iter()
+            .map(|(key, value)| (reverse_maps(&key), reverse_maps(&value)))
+            .collect::<Map>();
+        Any::from(map)
+    } else if value.is_set() {
+        let mut values = value.to_set().into_iter().collect::<Vec<_>>();
+        values.reverse();
+        Any::from(values.iter().map(reverse_maps).collect::<Set>())
+    } else if value.is_array() {
+        let values = value.to_array().into_iter().map(|v| reverse_maps(&v));
+        Any::from(Array::from(values.collect::<Vec<_>>()))
+    } else if value.is_pair() {
+        let (key, value) = value.to_pair().to_tuple();
+        Any::from(Pair::new(reverse_maps(&key), reverse_maps(&value)))
+    } else {
+        value.clone()
+    }
+}
+
+proptest! {
+    #[test]
+    fn eq_implies_equal_hash(a in value(), b in value()) {
+        if a == b {
+            prop_assert_eq!(hash_of(&a), hash_of(&b));
+        }
+    }
+
+    #[test]
+    fn eq_is_reflexive(a in value()) {
+        prop_assert!(a == a.clone());
+        prop_assert_eq!(hash_of(&a), hash_of(&a.clone()));
+    }
+
+    #[test]
+    fn eq_is_symmetric(a in value(), b in value()) {
+        prop_assert_eq!(a == b, b == a);
+    }
+
+    #[test]
+    fn partial_cmp_agrees_with_eq(a in value(), b in value()) {
+        prop_assert_eq!(a.partial_cmp(&b) == Some(std::cmp::Ordering::Equal), a == b);
+    }
+
+    #[test]
+    fn cmp_total_agrees_with_eq(a in value(), b in value()) {
+        prop_assert_eq!(a.cmp_total(&b) == std::cmp::Ordering::Equal, a == b);
+        prop_assert_eq!(a.cmp_total(&b), b.cmp_total(&a).reverse());
+    }
+
+    #[test]
+    fn entry_order_does_not_matter(a in value()) {
+        let b = reverse_maps(&a);
+        prop_assert!(a == b);
+        prop_assert_eq!(hash_of(&a), hash_of(&b));
+    }
+
+    #[test]
+    fn numbers_are_equal_across_types(n in -1000_i64..1000) {
+        let values = [
+            any(n),
+            any(n as i16),
+            any(n as f64),
+            any(n as f32),
+            Any::from(Decimal::from(n)),
+        ];
+
+        for value in &values {
+            prop_assert!(*value == values[0]);
+            prop_assert_eq!(hash_of(value), hash_of(&values[0]));
+        }
+    }
+}
+
+#[test]
+fn test_map_as_key() {
+    let a = map! { "x" => 1, "y" => 2 };
+    let b = map! { "y" => 2.0, "x" => 1_u8 };
+    assert_eq!(a, b);
+
+    let mut set = std::collections::HashSet::new();
+    set.insert(a);
+    assert!(set.contains(&b));
+
+    let mut outer = Map::new();
+    outer.set(b, "found");
+    assert_eq!(outer.get(&map! { "x" => 1, "y" => 2 }), Some(&any("found")));
+}
+
+#[test]
+fn test_function_identity() {
+    let f = function!(x => { x });
+    let g = function!(x => { x });
+
+    assert_eq!(f, f.clone());
+    assert_eq!(hash_of(&f), hash_of(&f.clone()));
+    assert_ne!(f, g);
+}
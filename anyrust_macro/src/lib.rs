@@ -1 +1,472 @@
+//! Derive macros for converting Rust types to and from `anyrust::Any`.
+//!
+//! - named-field structs <-> `Map` keyed by field name
+//! - tuple structs <-> `Array`
+//! - unit structs <-> null
+//! - enums <-> tagged `Map` (`{ "Variant": payload }`)
+//!
+//! Fields support `#[any(rename = "...")]`, `#[any(skip)]` and `#[any(default)]`.
+//! Variants support `#[any(rename = "...")]`.
 
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Fields, GenericParam, Generics, Ident,
+    LitStr,
+};
+
+/// Implements `anyrust::IntoAny` and `From<T> for anyrust::Any`.
+#[proc_macro_derive(IntoAny, attributes(any))]
+pub fn derive_into_any(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand_into_any(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `anyrust::FromAny`.
+#[proc_macro_derive(FromAny, attributes(any))]
+pub fn derive_from_any(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand_from_any(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// options given with `#[any(...)]`
+#[derive(Default)]
+struct Options {
+    rename: Option<String>,
+    skip: bool,
+    default: bool,
+}
+
+fn parse_options(attrs: &[syn::Attribute]) -> syn::Result<Options> {
+    let mut options = Options::default();
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("any")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                let name: LitStr = meta.value()?.parse()?;
+                options.rename = Some(name.value());
+            } else if meta.path.is_ident("skip") {
+                options.skip = true;
+            } else if meta.path.is_ident("default") {
+                options.default = true;
+            } else {
+                return Err(meta.error("expected `rename`, `skip` or `default`"));
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(options)
+}
+
+fn reject_options(attrs: &[syn::Attribute], what: &str) -> syn::Result<()> {
+    match attrs.iter().find(|attr| attr.path().is_ident("any")) {
+        Some(attr) => Err(syn::Error::new_spanned(
+            attr,
+            format!("#[any(...)] is not supported on {what}"),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// field of a struct or variant, with the binding used in generated code
+struct Field {
+    /// `name` or `0`
+    member: syn::Member,
+    /// local variable the field is bound to
+    binding: Ident,
+    /// key in the Map, or None for tuple fields
+    key: Option<String>,
+    /// segment added to the path of a `CastError`: the key, or the index of a tuple field
+    segment: String,
+    options: Options,
+}
+
+fn collect_fields(fields: &Fields) -> syn::Result<Vec<Field>> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let options = parse_options(&field.attrs)?;
+
+            let (member, binding, key) = match &field.ident {
+                Some(ident) => (
+                    syn::Member::Named(ident.clone()),
+                    format_ident!("__field_{}", ident),
+                    Some(
+                        options
+                            .rename
+                            .clone()
+                            .unwrap_or_else(|| ident.unraw_string()),
+                    ),
+                ),
+                None => {
+                    if options.rename.is_some() {
+                        return Err(syn::Error::new_spanned(
+                            field,
+                            "tuple fields cannot be renamed",
+                        ));
+                    }
+
+                    (
+                        syn::Member::Unnamed(index.into()),
+                        format_ident!("__field_{}", index),
+                        None,
+                    )
+                }
+            };
+
+            let segment = key.clone().unwrap_or_else(|| index.to_string());
+
+            Ok(Field {
+                member,
+                binding,
+                key,
+                segment,
+                options,
+            })
+        })
+        .collect()
+}
+
+trait UnrawString {
+    fn unraw_string(&self) -> String;
+}
+
+impl UnrawString for Ident {
+    fn unraw_string(&self) -> String {
+        let name = self.to_string();
+
+        match name.strip_prefix("r#") {
+            Some(name) => name.to_string(),
+            None => name,
+        }
+    }
+}
+
+fn add_bound(generics: &Generics, bound: syn::TypeParamBound) -> Generics {
+    let mut generics = generics.clone();
+
+    for param in &mut generics.params {
+        if let GenericParam::Type(param) = param {
+            param.bounds.push(bound.clone());
+        }
+    }
+
+    generics
+}
+
+/// Pattern that binds the (non-skipped) fields to their bindings.
+fn destructure(fields: &Fields, collected: &[Field]) -> TokenStream2 {
+    let bindings = collected.iter().map(|field| {
+        let member = &field.member;
+        let binding = &field.binding;
+
+        if field.options.skip {
+            quote! { #member: _ }
+        } else {
+            quote! { #member: #binding }
+        }
+    });
+
+    match fields {
+        Fields::Unit => quote! {},
+        _ => quote! { { #(#bindings),* } },
+    }
+}
+
+/// Builds an Any from fields already bound to their bindings.
+fn fields_into_any(fields: &Fields, collected: &[Field]) -> TokenStream2 {
+    let fields_to_write = collected.iter().filter(|field| !field.options.skip);
+
+    match fields {
+        Fields::Named(_) => {
+            let sets = fields_to_write.map(|field| {
+                let key = field.key.as_ref().unwrap();
+                let binding = &field.binding;
+
+                quote! { map.set(#key, ::anyrust::IntoAny::into_any(#binding)); }
+            });
+
+            quote! {{
+                let mut map = ::anyrust::Map::new();
+                #(#sets)*
+                ::anyrust::Any::from(map)
+            }}
+        }
+        Fields::Unnamed(_) => {
+            let pushes = fields_to_write.map(|field| {
+                let binding = &field.binding;
+
+                quote! { array.push(::anyrust::IntoAny::into_any(#binding)); }
+            });
+
+            quote! {{
+                let mut array = ::anyrust::Array::new();
+                #(#pushes)*
+                ::anyrust::Any::from(array)
+            }}
+        }
+        Fields::Unit => quote! { ::anyrust::Any::from(::anyrust::Null) },
+    }
+}
+
+/// Reads a single field from `source: Option<&Any>`.
+fn field_from_any(field: &Field, source: TokenStream2) -> TokenStream2 {
+    let segment = &field.segment;
+
+    let convert = quote! {
+        ::anyrust::FromAny::from_any(value)
+            .map_err(|error: ::anyrust::CastError| error.with_path(#segment))?
+    };
+
+    if field.options.skip {
+        quote! { ::std::default::Default::default() }
+    } else if field.options.default {
+        quote! {
+            match #source {
+                Some(value) => #convert,
+                None => ::std::default::Default::default(),
+            }
+        }
+    } else {
+        // a missing field is read as null, so that Option fields become None
+        quote! {
+            match #source {
+                Some(value) => #convert,
+                None => {
+                    let value = &*::anyrust::null;
+                    #convert
+                }
+            }
+        }
+    }
+}
+
+/// Builds `constructor { ... }` from `value: &Any`.
+fn fields_from_any(
+    constructor: TokenStream2,
+    fields: &Fields,
+    collected: &[Field],
+    type_name: &str,
+) -> TokenStream2 {
+    match fields {
+        Fields::Named(_) => {
+            let reads = collected.iter().map(|field| {
+                let member = &field.member;
+                let key = field.key.as_ref().unwrap();
+                let read = field_from_any(field, quote! { map.get(&::anyrust::Any::from(#key)) });
+
+                quote! { #member: #read }
+            });
+
+            quote! {{
+                let map = value.try_to_map()
+                    .map_err(|error| ::anyrust::CastError::new(error.from, #type_name))?;
+
+                #constructor { #(#reads),* }
+            }}
+        }
+        Fields::Unnamed(_) => {
+            let mut index = 0_isize;
+            let reads = collected.iter().map(|field| {
+                let member = &field.member;
+                let read = field_from_any(field, quote! { array.at(#index).as_ref() });
+
+                if !field.options.skip {
+                    index += 1;
+                }
+
+                quote! { #member: #read }
+            });
+            let reads = reads.collect::<Vec<_>>();
+
+            quote! {{
+                let array = value.try_to_array()
+                    .map_err(|error| ::anyrust::CastError::new(error.from, #type_name))?;
+
+                #constructor { #(#reads),* }
+            }}
+        }
+        Fields::Unit => quote! {{
+            <::anyrust::Null as ::anyrust::FromAny>::from_any(value)
+                .map_err(|error| ::anyrust::CastError::new(error.from, #type_name))?;
+
+            #constructor
+        }},
+    }
+}
+
+fn expand_into_any(input: DeriveInput) -> syn::Result<TokenStream2> {
+    reject_options(&input.attrs, "containers")?;
+
+    let name = &input.ident;
+    let generics = add_bound(&input.generics, parse_quote!(::anyrust::IntoAny));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let collected = collect_fields(&data.fields)?;
+            let pattern = destructure(&data.fields, &collected);
+            let build = fields_into_any(&data.fields, &collected);
+
+            quote! {
+                #[allow(unused_variables)]
+                let Self #pattern = self;
+                #build
+            }
+        }
+        Data::Enum(data) => {
+            let arms = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let options = parse_options(&variant.attrs)?;
+                    if options.skip || options.default {
+                        return Err(syn::Error::new_spanned(
+                            variant,
+                            "only `rename` is supported on variants",
+                        ));
+                    }
+
+                    let ident = &variant.ident;
+                    let tag = options.rename.unwrap_or_else(|| ident.unraw_string());
+                    let collected = collect_fields(&variant.fields)?;
+                    let pattern = destructure(&variant.fields, &collected);
+                    let build = fields_into_any(&variant.fields, &collected);
+
+                    Ok(quote! {
+                        Self::#ident #pattern => {
+                            let mut map = ::anyrust::Map::new();
+                            map.set(#tag, #build);
+                            ::anyrust::Any::from(map)
+                        }
+                    })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input,
+                "IntoAny cannot be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::anyrust::IntoAny for #name #ty_generics #where_clause {
+            fn into_any(self) -> ::anyrust::Any {
+                #body
+            }
+        }
+
+        impl #impl_generics ::std::convert::From<#name #ty_generics> for ::anyrust::Any #where_clause {
+            fn from(value: #name #ty_generics) -> Self {
+                ::anyrust::IntoAny::into_any(value)
+            }
+        }
+    })
+}
+
+fn expand_from_any(input: DeriveInput) -> syn::Result<TokenStream2> {
+    reject_options(&input.attrs, "containers")?;
+
+    let name = &input.ident;
+    let name_string = name.to_string();
+    let generics = add_bound(&input.generics, parse_quote!(::anyrust::FromAny));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let collected = collect_fields(&data.fields)?;
+            let build = fields_from_any(quote! { Self }, &data.fields, &collected, &name_string);
+
+            quote! { Ok(#build) }
+        }
+        Data::Enum(data) => {
+            let arms = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let options = parse_options(&variant.attrs)?;
+                    if options.skip || options.default {
+                        return Err(syn::Error::new_spanned(
+                            variant,
+                            "only `rename` is supported on variants",
+                        ));
+                    }
+
+                    let ident = &variant.ident;
+                    let tag = options.rename.unwrap_or_else(|| ident.unraw_string());
+                    let type_name = format!("{name}::{}", ident);
+                    let collected = collect_fields(&variant.fields)?;
+                    let build = fields_from_any(
+                        quote! { Self::#ident },
+                        &variant.fields,
+                        &collected,
+                        &type_name,
+                    );
+
+                    // the tag is a segment of the path, like a key
+                    Ok(quote! {
+                        #tag => (|| -> ::std::result::Result<Self, ::anyrust::CastError> { Ok(#build) })()
+                            .map_err(|error| error.with_path(#tag)),
+                    })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+
+            // a bare string is accepted for unit variants
+            quote! {
+                let (tag, payload) = if value.is_string() {
+                    (value.to_str(), ::anyrust::null.clone())
+                } else {
+                    let map = value.try_to_map()
+                        .map_err(|error| ::anyrust::CastError::new(error.from, #name_string))?;
+                    if map.length() != 1 {
+                        return Err(::anyrust::CastError::ambiguous_variant(#name_string));
+                    }
+
+                    let (tag, payload) = map.into_iter().next().unwrap();
+                    if !tag.is_string() {
+                        return Err(::anyrust::CastError::unknown_variant(&tag, #name_string));
+                    }
+                    (tag.to_str(), payload)
+                };
+                let value = &payload;
+
+                match tag.as_str() {
+                    #(#arms)*
+                    _ => Err(::anyrust::CastError::unknown_variant(&::anyrust::Any::from(tag), #name_string)),
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input,
+                "FromAny cannot be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::anyrust::FromAny for #name #ty_generics #where_clause {
+            fn from_any(value: &::anyrust::Any) -> ::std::result::Result<Self, ::anyrust::CastError> {
+                #body
+            }
+        }
+    })
+}
@@ -0,0 +1,226 @@
+use anyrust::*;
+use anyrust_macro::{FromAny, IntoAny};
+
+#[derive(Debug, Clone, PartialEq, IntoAny, FromAny)]
+struct Person {
+    name: String,
+    age: u32,
+    #[any(rename = "e-mail")]
+    email: Option<String>,
+    #[any(skip)]
+    cache: Vec<u8>,
+    #[any(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, IntoAny, FromAny)]
+struct Point(i32, i32);
+
+#[derive(Debug, Clone, PartialEq, IntoAny, FromAny)]
+struct Marker;
+
+#[derive(Debug, Clone, PartialEq, IntoAny, FromAny)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    #[any(rename = "rect")]
+    Rectangle {
+        width: f64,
+        height: f64,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, IntoAny, FromAny)]
+struct Drawing<T> {
+    shapes: Vec<Shape>,
+    origin: Point,
+    meta: T,
+}
+
+fn person() -> Person {
+    Person {
+        name: "John".into(),
+        age: 30,
+        email: None,
+        cache: vec![1, 2, 3],
+        tags: vec!["a".into()],
+    }
+}
+
+#[test]
+fn test_struct_into_any() {
+    let value = Any::from(person());
+
+    assert!(value.is_map());
+    assert_eq!(value.get("name"), any("John"));
+    assert_eq!(value.get("age"), any(30_u32));
+    assert!(value.get("e-mail").is_null());
+    assert_eq!(
+        value.to_json(),
+        r#"{"name":"John","age":30,"e-mail":null,"tags":["a"]}"#
+    );
+}
+
+#[test]
+fn test_struct_from_any() {
+    let value = Any::from(person());
+    let back = Person::from_any(&value).unwrap();
+
+    assert_eq!(
+        back,
+        Person {
+            cache: vec![],
+            ..person()
+        }
+    );
+
+    // missing Option fields become None, and missing default fields become Default::default()
+    let value = map! { "name" => "Jane", "age" => "25" };
+    let jane = Person::from_any(&value).unwrap();
+    assert_eq!(jane.age, 25);
+    assert_eq!(jane.email, None);
+    assert!(jane.tags.is_empty());
+}
+
+#[test]
+fn test_struct_from_any_errors() {
+    let value = map! { "name" => "Jane" };
+    assert_eq!(
+        Person::from_any(&value),
+        Err(CastError::new("Null", "integer").with_path("age"))
+    );
+
+    let value = map! { "name" => 1, "age" => 1 };
+    assert_eq!(
+        Person::from_any(&value),
+        Err(CastError::new("i32", "String").with_path("name"))
+    );
+
+    assert_eq!(
+        Person::from_any(&any(1)),
+        Err(CastError::new("i32", "Person"))
+    );
+}
+
+#[test]
+fn test_tuple_and_unit_struct() {
+    let value = Any::from(Point(1, 2));
+    assert_eq!(value.to_string(), "[1, 2]");
+    assert_eq!(Point::from_any(&value), Ok(Point(1, 2)));
+    assert_eq!(
+        Point::from_any(&array![1]),
+        Err(CastError::new("Null", "integer").with_path("1"))
+    );
+
+    let value = Any::from(Marker);
+    assert!(value.is_null());
+    assert_eq!(Marker::from_any(&value), Ok(Marker));
+    assert_eq!(
+        Marker::from_any(&any(1)),
+        Err(CastError::new("i32", "Marker"))
+    );
+}
+
+#[test]
+fn test_enum() {
+    let value = Any::from(Shape::Circle(1.5));
+    assert_eq!(value.to_json(), r#"{"Circle":[1.5]}"#);
+    assert_eq!(Shape::from_any(&value), Ok(Shape::Circle(1.5)));
+
+    let value = Any::from(Shape::Rectangle {
+        width: 2.0,
+        height: 3.0,
+    });
+    assert_eq!(value.to_json(), r#"{"rect":{"width":2,"height":3}}"#);
+    assert_eq!(
+        Shape::from_any(&value),
+        Ok(Shape::Rectangle {
+            width: 2.0,
+            height: 3.0
+        })
+    );
+
+    let value = Any::from(Shape::Empty);
+    assert_eq!(value.to_json(), r#"{"Empty":null}"#);
+    assert_eq!(Shape::from_any(&value), Ok(Shape::Empty));
+    assert_eq!(Shape::from_any(&any("Empty")), Ok(Shape::Empty));
+
+    assert_eq!(
+        Shape::from_any(&any("Triangle")),
+        Err(CastError::unknown_variant(&any("Triangle"), "Shape"))
+    );
+    assert_eq!(
+        Shape::from_any(&Any::parse_json(r#"{"Circle":"big"}"#).unwrap()),
+        Err(CastError::new("String", "Shape::Circle").with_path("Circle"))
+    );
+}
+
+#[test]
+fn test_enum_errors() {
+    let error =
+        Shape::from_any(&map! { "Empty" => null.clone(), "Circle" => array![1.0] }).unwrap_err();
+    assert_eq!(error.kind, CastErrorKind::AmbiguousVariant);
+    assert_eq!(
+        error.to_string(),
+        "expected a map with exactly one key for Shape"
+    );
+
+    let error = Shape::from_any(&map! {}).unwrap_err();
+    assert_eq!(error.kind, CastErrorKind::AmbiguousVariant);
+
+    let error = Shape::from_any(&map! { 1 => null.clone() }).unwrap_err();
+    assert_eq!(error.kind, CastErrorKind::UnknownVariant);
+    assert_eq!(error.from, "i32");
+
+    let error = Shape::from_any(&any("Triangle")).unwrap_err();
+    assert_eq!(error.to_string(), "unknown variant of Shape");
+}
+
+#[test]
+fn test_error_path() {
+    let error = Person::from_any(&map! { "name" => "Jane", "age" => "old" }).unwrap_err();
+    assert_eq!(error.path.as_deref(), Some("age"));
+    assert_eq!(error.to_string(), "cannot cast String to integer at age");
+
+    // every level prepends its segment
+    let value = map! {
+        "shapes" => array![map! { "Circle" => array!["big"] }],
+        "origin" => array![0, 0],
+        "meta" => null.clone(),
+    };
+    let error = Drawing::<Option<Person>>::from_any(&value).unwrap_err();
+    assert_eq!(error.path.as_deref(), Some("shapes.0.Circle.0"));
+    assert_eq!((error.from, error.to), ("String", "float"));
+    assert_eq!(value.path(error.path.as_deref().unwrap()), any("big"));
+
+    let value = map! {
+        "shapes" => array![],
+        "origin" => array![0, 0],
+        "meta" => map! { "name" => "Jane", "age" => "old" },
+    };
+    let error = Drawing::<Person>::from_any(&value).unwrap_err();
+    assert_eq!(error.path.as_deref(), Some("meta.age"));
+    assert_eq!(
+        error.to_string(),
+        "cannot cast String to integer at meta.age"
+    );
+}
+
+#[test]
+fn test_nested_and_generic() {
+    let drawing = Drawing {
+        shapes: vec![Shape::Empty, Shape::Circle(1.0)],
+        origin: Point(0, 0),
+        meta: Some(person()),
+    };
+
+    let value = Any::from(drawing.clone());
+    assert_eq!(value.get("origin").to_string(), "[0, 0]");
+    assert_eq!(value.get("meta").get("name"), any("John"));
+
+    let json = Any::parse_json(&value.to_json()).unwrap();
+    let back = Drawing::<Option<Person>>::from_any(&json).unwrap();
+    assert_eq!(back.shapes, drawing.shapes);
+    assert_eq!(back.origin, drawing.origin);
+    assert_eq!(back.meta.unwrap().name, "John");
+}